@@ -38,6 +38,9 @@
 use std::mem;
 use thin_vec::{thin_vec, ThinVec};
 
+#[cfg(test)]
+mod tests;
+
 /// A "Label" is an identifier of some point in sources,
 /// e.g. in the following code:
 ///
@@ -1745,6 +1748,18 @@ pub struct MacroDef {
     pub macro_rules: bool,
 }
 
+impl MacroDef {
+    /// Whether this is a `macro_rules!` definition.
+    pub fn is_macro_rules(&self) -> bool {
+        self.macro_rules
+    }
+
+    /// Whether this is a declarative macro 2.0 definition (`macro foo() {}`).
+    pub fn is_decl_macro(&self) -> bool {
+        !self.macro_rules
+    }
+}
+
 #[derive(Clone, Encodable, Decodable, Debug, Copy, Hash, Eq, PartialEq)]
 #[derive(HashStable_Generic)]
 pub enum StrStyle {
@@ -2704,6 +2719,14 @@ impl VisibilityKind {
     pub fn is_pub(&self) -> bool {
         matches!(self, VisibilityKind::Public)
     }
+
+    /// The path of a restricted visibility, like `crate::foo` in `pub(in crate::foo)`.
+    pub fn restricted_path(&self) -> Option<&Path> {
+        match self {
+            VisibilityKind::Restricted { path, .. } => Some(path),
+            _ => None,
+        }
+    }
 }
 
 /// Field definition in a struct, variant or union.
@@ -0,0 +1,44 @@
+use super::*;
+use rustc_span::create_default_session_globals_then;
+
+fn macro_def(macro_rules: bool) -> MacroDef {
+    let body = DelimArgs {
+        dspan: DelimSpan::dummy(),
+        delim: MacDelimiter::Brace,
+        tokens: TokenStream::default(),
+    };
+    MacroDef { body: P(body), macro_rules }
+}
+
+#[test]
+fn macro_def_kind() {
+    // `macro_rules! foo {}`
+    let legacy = macro_def(true);
+    assert!(legacy.is_macro_rules());
+    assert!(!legacy.is_decl_macro());
+
+    // `macro foo {}`
+    let decl_macro = macro_def(false);
+    assert!(decl_macro.is_decl_macro());
+    assert!(!decl_macro.is_macro_rules());
+}
+
+#[test]
+fn visibility_kind_restricted_path() {
+    create_default_session_globals_then(|| {
+        // `pub(in crate::foo)`
+        let segments = [kw::Crate, Symbol::intern("foo")]
+            .into_iter()
+            .map(|name| PathSegment::from_ident(Ident::with_dummy_span(name)))
+            .collect();
+        let path = Path { span: DUMMY_SP, segments, tokens: None };
+        let restricted =
+            VisibilityKind::Restricted { path: P(path), id: DUMMY_NODE_ID, shorthand: false };
+        let restricted_path = restricted.restricted_path().unwrap();
+        assert_eq!(restricted_path.segments.len(), 2);
+        assert_eq!(restricted_path.segments[1].ident.name.as_str(), "foo");
+
+        // `pub`
+        assert!(VisibilityKind::Public.restricted_path().is_none());
+    })
+}
@@ -311,7 +311,7 @@ fn pp_ann(&self) -> &dyn pprust_hir::PpAnn {
 impl<'tcx> pprust_hir::PpAnn for TypedAnnotation<'tcx> {
     fn nested(&self, state: &mut pprust_hir::State<'_>, nested: pprust_hir::Nested) {
         let old_maybe_typeck_results = self.maybe_typeck_results.get();
-        if let pprust_hir::Nested::Body(id) = nested {
+        if let pprust_hir::Nested::Body(id) | pprust_hir::Nested::ConstArg(id) = nested {
             self.maybe_typeck_results.set(Some(self.tcx.typeck_body(id)));
         }
         let pp_ann = &(&self.tcx.hir() as &dyn hir::intravisit::Map<'_>);
@@ -1,6 +1,7 @@
 use crate::def::{CtorKind, DefKind, Res};
 use crate::def_id::DefId;
 pub(crate) use crate::hir_id::{HirId, ItemLocalId, OwnerId};
+use crate::hir_id::{CRATE_HIR_ID, CRATE_OWNER_ID};
 use crate::intravisit::FnKind;
 use crate::LangItem;
 
@@ -13,6 +14,7 @@
 use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sorted_map::SortedMap;
+use rustc_data_structures::sync::{par_for_each_in, DynSend, DynSync};
 use rustc_error_messages::MultiSpan;
 use rustc_index::IndexVec;
 use rustc_macros::HashStable_Generic;
@@ -24,6 +26,7 @@
 use rustc_target::spec::abi::Abi;
 
 use smallvec::SmallVec;
+use std::collections::BTreeMap;
 use std::fmt;
 
 #[derive(Debug, Copy, Clone, HashStable_Generic)]
@@ -175,6 +178,21 @@ pub fn suggestion_position(&self) -> (LifetimeSuggestionPosition, Span) {
     pub fn is_static(&self) -> bool {
         self.res == LifetimeName::Static
     }
+
+    /// Whether `self` and `other` name the same lifetime parameter, comparing the
+    /// hygienic (macros 2.0) names. Two `'static`s are considered the same, while
+    /// anonymous lifetimes never are.
+    pub fn refers_to_same_param(&self, other: &Lifetime) -> bool {
+        match (self.res, other.res) {
+            (LifetimeName::Static, LifetimeName::Static) => true,
+            (LifetimeName::Param(_), LifetimeName::Param(_)) => {
+                !self.is_anonymous()
+                    && !other.is_anonymous()
+                    && self.ident.normalize_to_macros_2_0() == other.ident.normalize_to_macros_2_0()
+            }
+            _ => false,
+        }
+    }
 }
 
 /// A `Path` is essentially Rust's notion of a name; for instance,
@@ -192,10 +210,24 @@ pub struct Path<'hir, R = Res> {
 /// Up to three resolutions for type, value and macro namespaces.
 pub type UsePath<'hir> = Path<'hir, SmallVec<[Res; 3]>>;
 
-impl Path<'_> {
+impl<'hir> Path<'hir> {
     pub fn is_global(&self) -> bool {
         !self.segments.is_empty() && self.segments[0].ident.name == kw::PathRoot
     }
+
+    /// Returns the `DefId` this path resolves to, or `None` for locals, primitive types,
+    /// `Self` and unresolved paths.
+    pub fn res_def_id(&self) -> Option<DefId> {
+        self.res.opt_def_id()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn last_segment(&self) -> Option<&'hir PathSegment<'hir>> {
+        self.segments.last()
+    }
 }
 
 /// A segment of a path: an identifier, an optional lifetime, and a set of
@@ -267,7 +299,7 @@ pub enum GenericArg<'hir> {
     Infer(InferArg),
 }
 
-impl GenericArg<'_> {
+impl<'hir> GenericArg<'hir> {
     pub fn span(&self) -> Span {
         match self {
             GenericArg::Lifetime(l) => l.ident.span,
@@ -314,6 +346,39 @@ pub fn is_ty_or_const(&self) -> bool {
             GenericArg::Type(_) | GenericArg::Const(_) | GenericArg::Infer(_) => true,
         }
     }
+
+    pub fn is_lifetime(&self) -> bool {
+        matches!(self, GenericArg::Lifetime(_))
+    }
+
+    pub fn is_type(&self) -> bool {
+        matches!(self, GenericArg::Type(_))
+    }
+
+    pub fn is_const(&self) -> bool {
+        matches!(self, GenericArg::Const(_))
+    }
+
+    pub fn as_lifetime(&self) -> Option<&'hir Lifetime> {
+        match *self {
+            GenericArg::Lifetime(lifetime) => Some(lifetime),
+            _ => None,
+        }
+    }
+
+    pub fn as_type(&self) -> Option<&'hir Ty<'hir>> {
+        match *self {
+            GenericArg::Type(ty) => Some(ty),
+            _ => None,
+        }
+    }
+
+    pub fn as_const(&self) -> Option<&ConstArg> {
+        match self {
+            GenericArg::Const(ct) => Some(ct),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
@@ -395,6 +460,11 @@ pub fn has_lifetime_params(&self) -> bool {
         self.args.iter().any(|arg| matches!(arg, GenericArg::Lifetime(_)))
     }
 
+    #[inline]
+    pub fn has_const_params(&self) -> bool {
+        self.args.iter().any(|arg| matches!(arg, GenericArg::Const(_)))
+    }
+
     #[inline]
     /// This function returns the number of type and const generic params.
     /// It should only be used for diagnostics.
@@ -415,6 +485,16 @@ pub fn span_ext(&self) -> Option<Span> {
         Some(self.span_ext).filter(|span| !span.is_empty())
     }
 
+    /// The span covering all generic arguments and associated item bindings, without the
+    /// surrounding brackets. Returns `None` if there are neither arguments nor bindings.
+    pub fn args_and_bindings_span(&self) -> Option<Span> {
+        self.args
+            .iter()
+            .map(|arg| arg.span())
+            .chain(self.bindings.iter().map(|binding| binding.span))
+            .reduce(|lo, hi| lo.to(hi))
+    }
+
     pub fn is_empty(&self) -> bool {
         self.args.is_empty()
     }
@@ -502,6 +582,31 @@ pub enum GenericParamKind<'hir> {
     },
 }
 
+impl GenericParamKind<'_> {
+    pub fn descr(&self) -> &'static str {
+        match self {
+            GenericParamKind::Lifetime { .. } => "lifetime",
+            GenericParamKind::Type { .. } => "type",
+            GenericParamKind::Const { .. } => "const",
+        }
+    }
+}
+
+/// Whether `arg` is of the right kind (lifetime, type or const) to be passed for a parameter
+/// of kind `param`. An inferred argument `_` matches both type and const parameters.
+pub fn kind_matches_param(arg: &GenericArg<'_>, param: &GenericParamKind<'_>) -> bool {
+    match (arg, param) {
+        (GenericArg::Lifetime(_), GenericParamKind::Lifetime { .. })
+        | (GenericArg::Type(_), GenericParamKind::Type { .. })
+        | (GenericArg::Const(_), GenericParamKind::Const { .. })
+        | (
+            GenericArg::Infer(_),
+            GenericParamKind::Type { .. } | GenericParamKind::Const { .. },
+        ) => true,
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
 pub struct GenericParam<'hir> {
     pub hir_id: HirId,
@@ -528,6 +633,15 @@ pub fn is_impl_trait(&self) -> bool {
     pub fn is_elided_lifetime(&self) -> bool {
         matches!(self.kind, GenericParamKind::Lifetime { kind: LifetimeParamKind::Elided })
     }
+
+    /// Whether this is a type or const parameter with a default, like `T = u8`.
+    pub fn has_default(&self) -> bool {
+        matches!(
+            self.kind,
+            GenericParamKind::Type { default: Some(_), .. }
+                | GenericParamKind::Const { default: Some(_), .. }
+        )
+    }
 }
 
 /// Records where the generic parameter originated from.
@@ -575,10 +689,77 @@ pub const fn empty() -> &'hir Generics<'hir> {
         &NOPE
     }
 
+    /// Counts the lifetime, type and const parameters declared by these generics.
+    pub fn own_counts(&self) -> GenericParamCount {
+        let mut counts = GenericParamCount::default();
+        for param in self.params {
+            match param.kind {
+                GenericParamKind::Lifetime { .. } => counts.lifetimes += 1,
+                GenericParamKind::Type { .. } => counts.types += 1,
+                GenericParamKind::Const { .. } => counts.consts += 1,
+            }
+        }
+        counts
+    }
+
+    /// The lifetime parameters declared by these generics.
+    pub fn lifetimes(&self) -> impl Iterator<Item = &'hir GenericParam<'hir>> {
+        self.params
+            .iter()
+            .filter(|param| matches!(param.kind, GenericParamKind::Lifetime { .. }))
+    }
+
+    /// The type parameters declared by these generics, including synthetic `impl Trait` ones.
+    pub fn types(&self) -> impl Iterator<Item = &'hir GenericParam<'hir>> {
+        self.params.iter().filter(|param| matches!(param.kind, GenericParamKind::Type { .. }))
+    }
+
+    /// The const parameters declared by these generics.
+    pub fn consts(&self) -> impl Iterator<Item = &'hir GenericParam<'hir>> {
+        self.params.iter().filter(|param| matches!(param.kind, GenericParamKind::Const { .. }))
+    }
+
     pub fn get_named(&self, name: Symbol) -> Option<&GenericParam<'hir>> {
         self.params.iter().find(|&param| name == param.name.ident().name)
     }
 
+    /// The lifetimes that the lifetime parameter `name` must outlive, like `'b` for `'a` in
+    /// `<'a: 'b, 'b>` or `where 'a: 'b`. Bounds on the parameter itself are lowered to
+    /// region predicates as well, so this only looks at `self.predicates`.
+    pub fn lifetime_bounds(&self, name: Symbol) -> Vec<&'hir Lifetime> {
+        self.predicates
+            .iter()
+            .filter_map(|predicate| match predicate {
+                WherePredicate::RegionPredicate(predicate)
+                    if predicate.lifetime.ident.name == name =>
+                {
+                    Some(predicate.bounds)
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|bound| match bound {
+                GenericBound::Outlives(lifetime) => Some(*lifetime),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Like `get_named`, but only looks at lifetime parameters.
+    pub fn get_named_lifetime(&self, name: Symbol) -> Option<&'hir GenericParam<'hir>> {
+        self.lifetimes().find(|param| name == param.name.ident().name)
+    }
+
+    /// Like `get_named`, but only looks at type parameters.
+    pub fn get_named_type(&self, name: Symbol) -> Option<&'hir GenericParam<'hir>> {
+        self.types().find(|param| name == param.name.ident().name)
+    }
+
+    /// Like `get_named`, but only looks at const parameters.
+    pub fn get_named_const(&self, name: Symbol) -> Option<&'hir GenericParam<'hir>> {
+        self.consts().find(|param| name == param.name.ident().name)
+    }
+
     pub fn spans(&self) -> MultiSpan {
         if self.params.is_empty() {
             self.span.into()
@@ -956,6 +1137,257 @@ pub struct Crate<'hir> {
     pub opt_hir_hash: Option<Fingerprint>,
 }
 
+impl<'hir> Crate<'hir> {
+    pub fn item(&self, id: ItemId) -> &'hir Item<'hir> {
+        self.owners[id.owner_id.def_id].unwrap().node().expect_item()
+    }
+
+    pub fn trait_item(&self, id: TraitItemId) -> &'hir TraitItem<'hir> {
+        self.owners[id.owner_id.def_id].unwrap().node().expect_trait_item()
+    }
+
+    pub fn impl_item(&self, id: ImplItemId) -> &'hir ImplItem<'hir> {
+        self.owners[id.owner_id.def_id].unwrap().node().expect_impl_item()
+    }
+
+    /// Finds a macro definition (`macro_rules!` or `macro`) by name, along with its body.
+    ///
+    /// Macros are regular items in the HIR, so this scans every owner of the crate.
+    pub fn macro_by_name(&self, name: Symbol) -> Option<(&'hir Item<'hir>, &'hir ast::MacroDef)> {
+        self.items().find_map(|item| match item.kind {
+            ItemKind::Macro(macro_def, _) if item.ident.name == name => Some((item, macro_def)),
+            _ => None,
+        })
+    }
+
+    /// All trait definitions in the crate, along with their auto-ness, unsafety and items.
+    pub fn traits(
+        &self,
+    ) -> impl Iterator<Item = (&'hir Item<'hir>, IsAuto, Unsafety, &'hir [TraitItemRef])> + '_ {
+        self.items().filter_map(|item| match item.kind {
+            ItemKind::Trait(is_auto, unsafety, _, _, items) => {
+                Some((item, is_auto, unsafety, items))
+            }
+            _ => None,
+        })
+    }
+
+    /// All trait impls in the crate, along with the trait they implement.
+    pub fn impl_trait_refs(
+        &self,
+    ) -> impl Iterator<Item = (&'hir Item<'hir>, &'hir TraitRef<'hir>)> + '_ {
+        self.items().filter_map(|item| match item.kind {
+            ItemKind::Impl(Impl { of_trait: Some(trait_ref), .. }) => Some((item, trait_ref)),
+            _ => None,
+        })
+    }
+
+    /// Trait impls of a trait from another crate for a type that is not defined in this crate,
+    /// like `impl Display for Vec<u8>` or `impl Display for u8`. These are the impls that the
+    /// orphan check may reject, as far as the HIR alone can tell. The self type counts as local
+    /// if it is a path to a local type or a `dyn` of a local trait, possibly behind references.
+    /// Primitive types, tuples, slices, arrays, pointers and type parameters never do.
+    pub fn foreign_impls(&self) -> impl Iterator<Item = &'hir Item<'hir>> + '_ {
+        self.impl_trait_refs().filter_map(|(item, trait_ref)| {
+            let ItemKind::Impl(impl_) = item.kind else { return None };
+            let trait_is_foreign = !trait_ref.trait_def_id()?.is_local();
+            let self_ty_is_local = match impl_.self_ty.peel_refs().kind {
+                TyKind::Path(QPath::Resolved(None, path)) => match path.res {
+                    Res::Def(DefKind::TyParam, _) => false,
+                    res => res.opt_def_id().is_some_and(|def_id| def_id.is_local()),
+                },
+                TyKind::TraitObject(bounds, ..) => bounds
+                    .first()
+                    .and_then(|bound| bound.trait_ref.trait_def_id())
+                    .is_some_and(|def_id| def_id.is_local()),
+                _ => false,
+            };
+            (trait_is_foreign && !self_ty_is_local).then_some(item)
+        })
+    }
+
+    /// Finds a method named `name` in an inherent impl whose self type is a path to
+    /// `self_def_id`, such as `impl Foo { fn name(..) }` for the struct `Foo`.
+    pub fn find_inherent_method(
+        &self,
+        self_def_id: DefId,
+        name: Symbol,
+    ) -> Option<&'hir ImplItem<'hir>> {
+        self.items().find_map(|item| match item.kind {
+            ItemKind::Impl(Impl { of_trait: None, self_ty, items, .. })
+                if self_ty.as_path_def_id() == Some(self_def_id) =>
+            {
+                let method = items.iter().find(|item_ref| {
+                    matches!(item_ref.kind, AssocItemKind::Fn { .. }) && item_ref.ident.name == name
+                })?;
+                Some(method.resolve(self))
+            }
+            _ => None,
+        })
+    }
+
+    /// Finds the field named `field` of the struct `struct_def_id`, if that struct is defined
+    /// in this crate. The fields of tuple structs are named by their index, like `0`.
+    pub fn find_field(&self, struct_def_id: DefId, field: Symbol) -> Option<&'hir FieldDef<'hir>> {
+        let info = self.owners.get(struct_def_id.as_local()?)?.as_owner()?;
+        let OwnerNode::Item(Item { kind: ItemKind::Struct(data, _), .. }) = info.node() else {
+            return None;
+        };
+        data.fields().iter().find(|field_def| field_def.ident.name == field)
+    }
+
+    /// The number of generic parameters of each item, keyed by the item's `HirId`. Items
+    /// that cannot have generics, like `use` items, have a count of zero.
+    pub fn generic_param_counts(&self) -> FxHashMap<HirId, GenericParamCount> {
+        self.items()
+            .map(|item| {
+                let counts = item.kind.generics().map(Generics::own_counts).unwrap_or_default();
+                (item.hir_id(), counts)
+            })
+            .collect()
+    }
+
+    /// All items with an attribute named `attr_name`, such as `rustc_diagnostic_item`.
+    pub fn items_with_attr(
+        &self,
+        attr_name: Symbol,
+    ) -> impl Iterator<Item = &'hir Item<'hir>> + '_ {
+        self.owners.iter().filter_map(move |owner| {
+            let info = owner.as_owner()?;
+            let OwnerNode::Item(item) = info.node() else { return None };
+            let attrs = info.attrs.get(ItemLocalId::new(0));
+            ast::attr::contains_name(attrs, attr_name).then_some(item)
+        })
+    }
+
+    /// Classifies the owner of the body `body_id` by looking it up among the nodes of the
+    /// body's HIR owner, which include its closures and anonymous constants.
+    pub fn body_owner_kind(&self, body_id: BodyId) -> Option<BodyOwnerKind> {
+        let info = self.owners.get(body_id.hir_id.owner.def_id)?.as_owner()?;
+        info.nodes.nodes.iter().flatten().find_map(|parented_node| match parented_node.node {
+            Node::Item(Item { kind: ItemKind::Fn(.., body), .. })
+            | Node::TraitItem(TraitItem {
+                kind: TraitItemKind::Fn(_, TraitFn::Provided(body)), ..
+            })
+            | Node::ImplItem(ImplItem { kind: ImplItemKind::Fn(_, body), .. })
+                if *body == body_id =>
+            {
+                Some(BodyOwnerKind::Fn)
+            }
+            Node::Expr(Expr { kind: ExprKind::Closure(Closure { body, .. }), .. })
+                if *body == body_id =>
+            {
+                Some(BodyOwnerKind::Closure)
+            }
+            Node::Item(Item { kind: ItemKind::Const(_, body), .. })
+            | Node::TraitItem(TraitItem { kind: TraitItemKind::Const(_, Some(body)), .. })
+            | Node::ImplItem(ImplItem { kind: ImplItemKind::Const(_, body), .. })
+            | Node::AnonConst(AnonConst { body, .. })
+                if *body == body_id =>
+            {
+                Some(BodyOwnerKind::Const)
+            }
+            Node::Item(Item { kind: ItemKind::Static(_, mutbl, body), .. }) if *body == body_id => {
+                Some(BodyOwnerKind::Static(*mutbl))
+            }
+            _ => None,
+        })
+    }
+
+    /// The number of expressions of each kind in all bodies of the crate, keyed by the name
+    /// of their `ExprKind` variant, like `"MethodCall"`.
+    pub fn expr_kind_histogram(&self) -> BTreeMap<&'static str, usize> {
+        let mut histogram = BTreeMap::new();
+        for info in self.owners.iter().filter_map(|owner| owner.as_owner()) {
+            for (_, body) in info.nodes.bodies.iter() {
+                body.value.walk_always(|expr| {
+                    *histogram.entry(expr.kind.variant_name()).or_default() += 1;
+                });
+            }
+        }
+        histogram
+    }
+
+    /// All closure expressions in the crate, along with their capture clause and body.
+    pub fn closures(
+        &self,
+    ) -> impl Iterator<Item = (&'hir Expr<'hir>, CaptureBy, BodyId)> + '_ {
+        self.owners
+            .iter()
+            .filter_map(|owner| owner.as_owner())
+            .flat_map(|info| info.nodes.nodes.iter().flatten())
+            .filter_map(|parented_node| {
+                let Node::Expr(expr) = parented_node.node else { return None };
+                let ExprKind::Closure(closure) = expr.kind else { return None };
+                Some((expr, closure.capture_clause, closure.body))
+            })
+    }
+
+    /// The module hierarchy of the crate, starting at the crate root.
+    pub fn module_tree(&self) -> ModuleNode {
+        self.module_node(CRATE_HIR_ID, self.root_module())
+    }
+
+    /// The `main` function defined in the root module of the crate, if any.
+    pub fn root_main_fn(&self) -> Option<&'hir Item<'hir>> {
+        self.root_module()
+            .item_ids
+            .iter()
+            .map(|&id| self.item(id))
+            .find(|item| matches!(item.kind, ItemKind::Fn(..)) && item.ident.name == sym::main)
+    }
+
+    fn root_module(&self) -> &'hir Mod<'hir> {
+        let OwnerNode::Crate(root) = self.owners[CRATE_OWNER_ID.def_id].unwrap().node() else {
+            panic!("crate root is not a module")
+        };
+        root
+    }
+
+    fn module_node(&self, hir_id: HirId, module: &Mod<'hir>) -> ModuleNode {
+        let children = module
+            .item_ids
+            .iter()
+            .filter_map(|&id| match self.item(id).kind {
+                ItemKind::Mod(child) => Some(self.module_node(id.hir_id(), child)),
+                _ => None,
+            })
+            .collect();
+        ModuleNode { hir_id, item_ids: module.item_ids.to_vec(), children }
+    }
+
+    fn items(&self) -> impl Iterator<Item = &'hir Item<'hir>> + '_ {
+        self.owners.iter().filter_map(|owner| match owner.as_owner()?.node() {
+            OwnerNode::Item(item) => Some(item),
+            _ => None,
+        })
+    }
+
+    /// Calls `f` on every body of every owner in the crate.
+    ///
+    /// With the parallel compiler, bodies may be visited concurrently and the order
+    /// in which `f` is called is unspecified.
+    pub fn par_for_each_body(&self, f: impl Fn(BodyId, &Body<'hir>) + DynSend + DynSync) {
+        par_for_each_in(&self.owners.raw[..], |owner| {
+            if let MaybeOwner::Owner(info) = owner {
+                for (_, body) in info.nodes.bodies.iter() {
+                    f(body.id(), body)
+                }
+            }
+        })
+    }
+}
+
+/// A module in the tree returned by [`Crate::module_tree`].
+#[derive(Debug)]
+pub struct ModuleNode {
+    /// The `HirId` of the module: `CRATE_HIR_ID` for the crate root, or the `mod` item.
+    pub hir_id: HirId,
+    /// The items directly inside the module, including its child modules.
+    pub item_ids: Vec<ItemId>,
+    pub children: Vec<ModuleNode>,
+}
+
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
 pub struct Closure<'hir> {
     pub def_id: LocalDefId,
@@ -994,6 +1426,14 @@ pub struct Block<'hir> {
 }
 
 impl<'hir> Block<'hir> {
+    /// The block's tail expression, if any.
+    pub fn tail_expr(&self) -> Option<&'hir Expr<'hir>> {
+        self.expr
+    }
+
+    /// Follows tail expressions that are themselves blocks, like the `{ x }` in `{ { x } }`,
+    /// and returns the deepest block reached. This stops at the first block whose tail is not
+    /// a block, but does not look at the statements of the blocks it passes through.
     pub fn innermost_block(&self) -> &Block<'hir> {
         let mut block = self;
         while let Some(Expr { kind: ExprKind::Block(inner_block, _), .. }) = block.expr {
@@ -1001,6 +1441,15 @@ pub fn innermost_block(&self) -> &Block<'hir> {
         }
         block
     }
+
+    /// The expression that produces the block's value: its tail expression or, if there is
+    /// none, the expression of the last statement, such as a trailing `return x;`.
+    pub fn value_expr(&self) -> Option<&'hir Expr<'hir>> {
+        self.expr.or_else(|| match self.stmts.last()?.kind {
+            StmtKind::Semi(expr) | StmtKind::Expr(expr) => Some(expr),
+            StmtKind::Local(_) | StmtKind::Item(_) => None,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
@@ -1075,6 +1524,23 @@ pub fn walk_always(&self, mut it: impl FnMut(&Pat<'_>)) {
             true
         })
     }
+
+    /// The path of a path pattern, such as a unit variant or constant (`None`, `FOO`).
+    pub fn as_path(&self) -> Option<&QPath<'hir>> {
+        match &self.kind {
+            PatKind::Path(qpath) => Some(qpath),
+            _ => None,
+        }
+    }
+
+    /// The path, fields and whether there is a rest pattern `..` of a struct pattern such as
+    /// `Foo { x, .. }`.
+    pub fn as_struct(&self) -> Option<(&QPath<'hir>, &'hir [PatField<'hir>], bool)> {
+        match &self.kind {
+            PatKind::Struct(qpath, fields, has_rest) => Some((qpath, fields, *has_rest)),
+            _ => None,
+        }
+    }
 }
 
 /// A single field in a struct pattern.
@@ -1293,6 +1759,31 @@ pub fn is_comparison(self) -> bool {
     pub fn is_by_value(self) -> bool {
         !self.is_comparison()
     }
+
+    /// The comparison operator to use when swapping the operands, so that `a < b` can be
+    /// rewritten as `b > a`. Returns `None` for operators that are not comparisons.
+    pub fn swap(self) -> Option<BinOpKind> {
+        match self {
+            BinOpKind::Eq => Some(BinOpKind::Eq),
+            BinOpKind::Ne => Some(BinOpKind::Ne),
+            BinOpKind::Lt => Some(BinOpKind::Gt),
+            BinOpKind::Le => Some(BinOpKind::Ge),
+            BinOpKind::Gt => Some(BinOpKind::Lt),
+            BinOpKind::Ge => Some(BinOpKind::Le),
+            BinOpKind::And
+            | BinOpKind::Or
+            | BinOpKind::Add
+            | BinOpKind::Sub
+            | BinOpKind::Mul
+            | BinOpKind::Div
+            | BinOpKind::Rem
+            | BinOpKind::BitXor
+            | BinOpKind::BitAnd
+            | BinOpKind::BitOr
+            | BinOpKind::Shl
+            | BinOpKind::Shr => None,
+        }
+    }
 }
 
 impl Into<ast::BinOpKind> for BinOpKind {
@@ -1355,6 +1846,16 @@ pub struct Stmt<'hir> {
     pub span: Span,
 }
 
+impl Stmt<'_> {
+    /// The span of the whole statement, including the trailing `;` of a `StmtKind::Semi`.
+    ///
+    /// The `;` isn't tracked separately: lowering already gives statements the span of
+    /// the whole statement, so this is `self.span`.
+    pub fn full_span(&self) -> Span {
+        self.span
+    }
+}
+
 /// The contents of a statement.
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
 pub enum StmtKind<'hir> {
@@ -1371,6 +1872,30 @@ pub enum StmtKind<'hir> {
     Semi(&'hir Expr<'hir>),
 }
 
+impl StmtKind<'_> {
+    pub fn descr(&self) -> &'static str {
+        match self {
+            StmtKind::Local(..) => "local binding",
+            StmtKind::Item(..) => "item",
+            StmtKind::Expr(..) => "expression",
+            StmtKind::Semi(..) => "expression statement",
+        }
+    }
+
+    pub fn is_local(&self) -> bool {
+        matches!(self, StmtKind::Local(_))
+    }
+
+    pub fn is_item(&self) -> bool {
+        matches!(self, StmtKind::Item(_))
+    }
+
+    /// Whether this is an expression statement, with or without a trailing `;`.
+    pub fn is_expr(&self) -> bool {
+        matches!(self, StmtKind::Expr(_) | StmtKind::Semi(_))
+    }
+}
+
 /// Represents a `let` statement (i.e., `let <pat>:<ty> = <init>;`).
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
 pub struct Local<'hir> {
@@ -1388,6 +1913,17 @@ pub struct Local<'hir> {
     pub source: LocalSource,
 }
 
+impl<'hir> Local<'hir> {
+    pub fn pat_and_init(&self) -> (&'hir Pat<'hir>, Option<&'hir Expr<'hir>>) {
+        (self.pat, self.init)
+    }
+
+    /// Whether this `let` was introduced by lowering rather than written by the user.
+    pub fn source_is_desugared(&self) -> bool {
+        !matches!(self.source, LocalSource::Normal)
+    }
+}
+
 /// Represents a single arm of a `match` expression, e.g.
 /// `<pat> (if <guard>) => <body>`.
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
@@ -1683,7 +2219,7 @@ pub struct Expr<'hir> {
     pub span: Span,
 }
 
-impl Expr<'_> {
+impl<'hir> Expr<'hir> {
     pub fn precedence(&self) -> ExprPrecedence {
         match self.kind {
             ExprKind::ConstBlock(_) => ExprPrecedence::ConstBlock,
@@ -1815,6 +2351,26 @@ pub fn peel_borrows(&self) -> &Self {
         expr
     }
 
+    /// Strips any number of `*` operators off this expression.
+    pub fn peel_derefs(&self) -> &Self {
+        let mut expr = self;
+        while let ExprKind::Unary(UnOp::Deref, inner) = &expr.kind {
+            expr = inner;
+        }
+        expr
+    }
+
+    /// The number of `*` operators that [`Expr::peel_derefs`] strips, e.g. `2` for `**x`.
+    pub fn deref_count(&self) -> usize {
+        let mut count = 0;
+        let mut expr = self;
+        while let ExprKind::Unary(UnOp::Deref, inner) = &expr.kind {
+            count += 1;
+            expr = inner;
+        }
+        count
+    }
+
     pub fn can_have_side_effects(&self) -> bool {
         match self.peel_drop_temps().kind {
             ExprKind::Path(_) | ExprKind::Lit(_) | ExprKind::OffsetOf(..) => false,
@@ -1871,6 +2427,145 @@ pub fn can_have_side_effects(&self) -> bool {
         }
     }
 
+    /// Whether this expression only consists of operations that are allowed in const
+    /// contexts: literals, arithmetic, arrays, tuples, struct literals and paths to
+    /// constants.
+    ///
+    /// This is a conservative, purely syntactic check: it returns `false` for every call
+    /// (even to `const fn`s), method call, closure, loop, `yield` and assignment, and
+    /// doesn't look at types, so e.g. overloaded operators are not detected.
+    pub fn is_const_candidate(&self) -> bool {
+        match self.kind {
+            ExprKind::Lit(_) | ExprKind::ConstBlock(_) | ExprKind::OffsetOf(..) => true,
+            ExprKind::Path(QPath::Resolved(_, path)) => matches!(
+                path.res,
+                Res::Def(
+                    DefKind::Const
+                        | DefKind::AssocConst
+                        | DefKind::ConstParam
+                        | DefKind::Ctor(_, CtorKind::Const),
+                    _
+                )
+            ),
+            ExprKind::Array(exprs) | ExprKind::Tup(exprs) => {
+                exprs.iter().all(|e| e.is_const_candidate())
+            }
+            ExprKind::Binary(_, lhs, rhs) | ExprKind::Index(lhs, rhs) => {
+                lhs.is_const_candidate() && rhs.is_const_candidate()
+            }
+            ExprKind::Unary(_, e)
+            | ExprKind::Cast(e, _)
+            | ExprKind::Type(e, _)
+            | ExprKind::Field(e, _)
+            | ExprKind::AddrOf(.., e)
+            | ExprKind::DropTemps(e)
+            | ExprKind::Repeat(e, _) => e.is_const_candidate(),
+            ExprKind::Struct(_, fields, base) => {
+                fields.iter().all(|field| field.expr.is_const_candidate())
+                    && base.map_or(true, |base| base.is_const_candidate())
+            }
+            ExprKind::Path(_)
+            | ExprKind::Call(..)
+            | ExprKind::MethodCall(..)
+            | ExprKind::Closure(..)
+            | ExprKind::Loop(..)
+            | ExprKind::Yield(..)
+            | ExprKind::Assign(..)
+            | ExprKind::AssignOp(..)
+            | ExprKind::Let(..)
+            | ExprKind::If(..)
+            | ExprKind::Match(..)
+            | ExprKind::Block(..)
+            | ExprKind::Break(..)
+            | ExprKind::Continue(..)
+            | ExprKind::Ret(..)
+            | ExprKind::InlineAsm(..)
+            | ExprKind::Err(_) => false,
+        }
+    }
+
+    /// Whether this is a block whose tail expression (or, lacking one, whose last
+    /// statement) syntactically diverges, such as `{ return x; }` or `{ foo(); continue }`.
+    pub fn block_diverges(&self) -> bool {
+        let ExprKind::Block(block, _) = self.kind else { return false };
+        let last = match (block.expr, block.stmts.last()) {
+            (Some(expr), _) => expr,
+            (None, Some(Stmt { kind: StmtKind::Expr(expr) | StmtKind::Semi(expr), .. })) => expr,
+            _ => return false,
+        };
+        match last.peel_drop_temps().kind {
+            ExprKind::Ret(_) | ExprKind::Break(..) | ExprKind::Continue(_) => true,
+            _ => last.block_diverges(),
+        }
+    }
+
+    /// Collects the `HirId`s of all local variables referenced by path expressions in
+    /// this expression, in visiting order. Nested closure bodies are not visited.
+    pub fn referenced_locals(&self) -> Vec<HirId> {
+        use crate::intravisit::Visitor;
+        struct MyVisitor(Vec<HirId>);
+        impl<'v> Visitor<'v> for MyVisitor {
+            fn visit_expr(&mut self, ex: &'v Expr<'v>) {
+                if let ExprKind::Path(QPath::Resolved(None, Path { res: Res::Local(id), .. })) =
+                    ex.kind
+                {
+                    self.0.push(*id);
+                }
+                crate::intravisit::walk_expr(self, ex);
+            }
+        }
+
+        let mut my_visitor = MyVisitor(vec![]);
+        my_visitor.visit_expr(self);
+        my_visitor.0
+    }
+
+    /// Walk the expression in pre-order, without descending into nested bodies
+    /// (closures, inline consts) or items.
+    ///
+    /// If `it(expr)` returns `false`, the children are not visited.
+    pub fn walk(&self, it: impl FnMut(&Expr<'_>) -> bool) {
+        use crate::intravisit::Visitor;
+        struct MyVisitor<F>(F);
+        impl<'v, F: FnMut(&Expr<'_>) -> bool> Visitor<'v> for MyVisitor<F> {
+            fn visit_expr(&mut self, ex: &'v Expr<'v>) {
+                if (self.0)(ex) {
+                    crate::intravisit::walk_expr(self, ex);
+                }
+            }
+        }
+
+        MyVisitor(it).visit_expr(self)
+    }
+
+    /// Walk the expression in pre-order.
+    ///
+    /// If you always want to recurse, prefer this method over `walk`.
+    pub fn walk_always(&self, mut it: impl FnMut(&Expr<'_>)) {
+        self.walk(|e| {
+            it(e);
+            true
+        })
+    }
+
+    /// Whether this is a `loop { .. }` that is never exited by a `break`. Breaks out of
+    /// nested loops do not count, since they target the inner loop.
+    pub fn is_infinite_loop(&self) -> bool {
+        if !matches!(self.kind, ExprKind::Loop(_, _, LoopSource::Loop, _)) {
+            return false;
+        }
+        let mut breaks = false;
+        self.walk(|e| {
+            if let ExprKind::Break(destination, _) = e.kind
+                && destination.target_id.ok() == Some(self.hir_id)
+            {
+                breaks = true;
+            }
+            !breaks
+        });
+        !breaks
+    }
+
     /// To a first-order approximation, is this a pattern?
     pub fn is_approximately_pattern(&self) -> bool {
         match &self.kind {
@@ -1891,6 +2586,106 @@ pub fn method_ident(&self) -> Option<Ident> {
             _ => None,
         }
     }
+
+    /// The label targeted by a `break` or `continue`, if one was written explicitly.
+    pub fn control_flow_label(&self) -> Option<Label> {
+        match self.kind {
+            ExprKind::Break(destination, _) | ExprKind::Continue(destination) => destination.label,
+            _ => None,
+        }
+    }
+
+    /// If this is a field access `base.field`, returns the base expression and the field.
+    pub fn as_field(&self) -> Option<(&Expr<'hir>, Ident)> {
+        match self.kind {
+            ExprKind::Field(base, field) => Some((base, field)),
+            _ => None,
+        }
+    }
+
+    /// If this is an `if` expression, returns its condition, then-block and optional
+    /// else-branch. The blocks are `ExprKind::Block` expressions.
+    pub fn as_if(&self) -> Option<(&'hir Expr<'hir>, &'hir Expr<'hir>, Option<&'hir Expr<'hir>>)> {
+        match self.kind {
+            ExprKind::If(cond, then, els) => Some((cond, then, els)),
+            _ => None,
+        }
+    }
+
+    /// If this is a borrow like `&x`, `&mut x` or `&raw const x`, returns the kind of
+    /// borrow, its mutability and the borrowed expression.
+    pub fn as_addr_of(&self) -> Option<(BorrowKind, Mutability, &'hir Expr<'hir>)> {
+        match self.kind {
+            ExprKind::AddrOf(kind, mutbl, expr) => Some((kind, mutbl, expr)),
+            _ => None,
+        }
+    }
+
+    /// If this is a `match` expression, returns its scrutinee, arms and source. Desugared
+    /// matches, like those of `for` loops and `?`, are included.
+    pub fn as_match(&self) -> Option<(&'hir Expr<'hir>, &'hir [Arm<'hir>], MatchSource)> {
+        match self.kind {
+            ExprKind::Match(scrutinee, arms, source) => Some((scrutinee, arms, source)),
+            _ => None,
+        }
+    }
+
+    /// If this is an array repeat expression `[elem; count]`, returns the element and the
+    /// count. Repeat expressions with an inferred count, like `[elem; _]`, return `None`.
+    pub fn as_repeat(&self) -> Option<(&Expr<'hir>, &AnonConst)> {
+        match self.kind {
+            ExprKind::Repeat(elem, ArrayLen::Body(ref count)) => Some((elem, count)),
+            _ => None,
+        }
+    }
+
+    /// The method calls of a chain like `a.b().c()`, innermost first, as the method's
+    /// segment and arguments (`[(b, []), (c, [])]`). The innermost receiver is not included.
+    pub fn method_chain(&self) -> Vec<(&'hir PathSegment<'hir>, &'hir [Expr<'hir>])> {
+        let mut chain = vec![];
+        let mut expr = self;
+        while let ExprKind::MethodCall(segment, receiver, args, _) = expr.kind {
+            chain.push((segment, args));
+            expr = receiver;
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// If this is an unlabeled block `{ expr }` without any statements, returns its tail
+    /// expression.
+    pub fn is_pure_block(&self) -> Option<&Expr<'hir>> {
+        match self.kind {
+            ExprKind::Block(Block { stmts: [], expr: Some(tail), .. }, None) => Some(tail),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an addition with a string literal on the left, like `"a" + x`.
+    pub fn is_str_lit_add(&self) -> bool {
+        match self.kind {
+            ExprKind::Binary(op, lhs, _) if op.node == BinOpKind::Add => {
+                matches!(lhs.kind, ExprKind::Lit(Spanned { node: LitKind::Str(..), .. }))
+            }
+            _ => false,
+        }
+    }
+
+    /// If this is a comparison with another comparison as one of its operands, like
+    /// `a < b < c` (which parses as `(a < b) < c`), returns the operands of the outer one.
+    pub fn is_nested_comparison(&self) -> Option<(&Expr<'hir>, &Expr<'hir>)> {
+        let is_comparison = |expr: &Expr<'_>| {
+            matches!(expr.kind, ExprKind::Binary(op, ..) if op.node.is_comparison())
+        };
+        match self.kind {
+            ExprKind::Binary(op, lhs, rhs)
+                if op.node.is_comparison() && (is_comparison(lhs) || is_comparison(rhs)) =>
+            {
+                Some((lhs, rhs))
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Checks if the specified expression is a built-in range literal.
@@ -2043,6 +2838,93 @@ pub enum ExprKind<'hir> {
     Err(rustc_span::ErrorGuaranteed),
 }
 
+impl ExprKind<'_> {
+    pub fn descr(&self) -> &'static str {
+        match self {
+            ExprKind::ConstBlock(_) => "inline constant",
+            ExprKind::Array(_) => "array",
+            ExprKind::Call(..) => "function call",
+            ExprKind::MethodCall(..) => "method call",
+            ExprKind::Tup(_) => "tuple",
+            ExprKind::Binary(..) => "binary operation",
+            ExprKind::Unary(..) => "unary operation",
+            ExprKind::Lit(_) => "literal",
+            ExprKind::Cast(..) => "cast",
+            ExprKind::Type(..) => "type ascription",
+            // Desugarings don't show up in the source, describe what they wrap instead.
+            ExprKind::DropTemps(expr) => expr.kind.descr(),
+            ExprKind::Let(_) => "`let` expression",
+            ExprKind::If(..) => "`if` expression",
+            ExprKind::Loop(..) => "loop",
+            ExprKind::Match(..) => "`match` expression",
+            ExprKind::Closure(_) => "closure",
+            ExprKind::Block(..) => "block",
+            ExprKind::Assign(..) => "assignment",
+            ExprKind::AssignOp(..) => "compound assignment",
+            ExprKind::Field(..) => "field access",
+            ExprKind::Index(..) => "indexing expression",
+            ExprKind::Path(_) => "path",
+            ExprKind::AddrOf(..) => "borrow",
+            ExprKind::Break(..) => "`break` expression",
+            ExprKind::Continue(_) => "`continue` expression",
+            ExprKind::Ret(_) => "`return` expression",
+            ExprKind::InlineAsm(_) => "inline assembly",
+            ExprKind::OffsetOf(..) => "`offset_of!` expression",
+            ExprKind::Struct(..) => "struct literal",
+            ExprKind::Repeat(..) => "array repeat expression",
+            ExprKind::Yield(..) => "`yield` expression",
+            ExprKind::Err(_) => "invalid expression",
+        }
+    }
+
+    /// The label of a loop or block, like `'a` in `'a: loop {}` or `'a: { .. }`. Desugared
+    /// `while` and `for` loops keep the label of the loop they were written as.
+    pub fn loop_label(&self) -> Option<Label> {
+        match self {
+            ExprKind::Loop(_, label, ..) | ExprKind::Block(_, label) => *label,
+            _ => None,
+        }
+    }
+
+    /// The name of this variant, like `"MethodCall"`.
+    fn variant_name(&self) -> &'static str {
+        match self {
+            ExprKind::ConstBlock(..) => "ConstBlock",
+            ExprKind::Array(..) => "Array",
+            ExprKind::Call(..) => "Call",
+            ExprKind::MethodCall(..) => "MethodCall",
+            ExprKind::Tup(..) => "Tup",
+            ExprKind::Binary(..) => "Binary",
+            ExprKind::Unary(..) => "Unary",
+            ExprKind::Lit(..) => "Lit",
+            ExprKind::Cast(..) => "Cast",
+            ExprKind::Type(..) => "Type",
+            ExprKind::DropTemps(..) => "DropTemps",
+            ExprKind::Let(..) => "Let",
+            ExprKind::If(..) => "If",
+            ExprKind::Loop(..) => "Loop",
+            ExprKind::Match(..) => "Match",
+            ExprKind::Closure(..) => "Closure",
+            ExprKind::Block(..) => "Block",
+            ExprKind::Assign(..) => "Assign",
+            ExprKind::AssignOp(..) => "AssignOp",
+            ExprKind::Field(..) => "Field",
+            ExprKind::Index(..) => "Index",
+            ExprKind::Path(..) => "Path",
+            ExprKind::AddrOf(..) => "AddrOf",
+            ExprKind::Break(..) => "Break",
+            ExprKind::Continue(..) => "Continue",
+            ExprKind::Ret(..) => "Ret",
+            ExprKind::InlineAsm(..) => "InlineAsm",
+            ExprKind::OffsetOf(..) => "OffsetOf",
+            ExprKind::Struct(..) => "Struct",
+            ExprKind::Repeat(..) => "Repeat",
+            ExprKind::Yield(..) => "Yield",
+            ExprKind::Err(_) => "Err",
+        }
+    }
+}
+
 /// Represents an optionally `Self`-qualified value/type path or associated extension.
 ///
 /// To resolve the path to a `DefId`, call [`qpath_res`].
@@ -2080,6 +2962,16 @@ pub fn span(&self) -> Span {
         }
     }
 
+    /// Returns the `Self` type of this `QPath`, if it has one. For example, `Vec<T>` in
+    /// `<Vec<T> as Clone>::clone` and `T` in `<T>::default`.
+    pub fn qself_ty(&self) -> Option<&'hir Ty<'hir>> {
+        match *self {
+            QPath::Resolved(qself, _) => qself,
+            QPath::TypeRelative(qself, _) => Some(qself),
+            QPath::LangItem(..) => None,
+        }
+    }
+
     /// Returns the span of the qself of this `QPath`. For example, `()` in
     /// `<() as Trait>::method`.
     pub fn qself_span(&self) -> Span {
@@ -2152,6 +3044,28 @@ pub const fn name(self) -> &'static str {
             FormatArgs => "format_args!()",
         }
     }
+
+    /// Whether this `match` was produced by lowering some other construct, rather than
+    /// written by the user.
+    #[inline]
+    pub const fn is_desugared(self) -> bool {
+        use MatchSource::*;
+        match self {
+            Normal => false,
+            ForLoopDesugar | TryDesugar | AwaitDesugar | FormatArgs => true,
+        }
+    }
+
+    /// Whether this `match` is part of a loop desugaring. `while` loops are lowered to a
+    /// `loop` around an `if`, so only `for` loops produce such a `match`.
+    #[inline]
+    pub const fn is_loop_desugar(self) -> bool {
+        use MatchSource::*;
+        match self {
+            ForLoopDesugar => true,
+            Normal | TryDesugar | AwaitDesugar | FormatArgs => false,
+        }
+    }
 }
 
 /// The loop type that yielded an `ExprKind::Loop`.
@@ -2173,6 +3087,18 @@ pub fn name(self) -> &'static str {
             LoopSource::ForLoop => "for",
         }
     }
+
+    pub fn is_loop(self) -> bool {
+        matches!(self, LoopSource::Loop)
+    }
+
+    pub fn is_while(self) -> bool {
+        matches!(self, LoopSource::While)
+    }
+
+    pub fn is_for(self) -> bool {
+        matches!(self, LoopSource::ForLoop)
+    }
 }
 
 #[derive(Copy, Clone, Debug, HashStable_Generic)]
@@ -2255,6 +3181,18 @@ pub struct FnSig<'hir> {
     pub span: Span,
 }
 
+impl FnSig<'_> {
+    /// The span from the first parameter's type to the return type. If there are no
+    /// parameters, this is just the span of the return type.
+    pub fn decl_span(&self) -> Span {
+        let output = self.decl.output.span();
+        match self.decl.inputs.first() {
+            Some(input) => input.span.to(output),
+            None => output,
+        }
+    }
+}
+
 // The bodies for items are stored "out of line", in a separate
 // hashmap in the `Crate`. Here we just record the hir-id of the item
 // so it can fetched later.
@@ -2345,6 +3283,16 @@ pub enum TraitItemKind<'hir> {
     Type(GenericBounds<'hir>, Option<&'hir Ty<'hir>>),
 }
 
+impl TraitItemKind<'_> {
+    pub fn descr(&self) -> &'static str {
+        match self {
+            TraitItemKind::Const(..) => "associated constant",
+            TraitItemKind::Fn(..) => "method",
+            TraitItemKind::Type(..) => "associated type",
+        }
+    }
+}
+
 // The bodies for items are stored "out of line", in a separate
 // hashmap in the `Crate`. Here we just record the hir-id of the item
 // so it can fetched later.
@@ -2423,6 +3371,16 @@ pub enum ImplItemKind<'hir> {
     Type(&'hir Ty<'hir>),
 }
 
+impl ImplItemKind<'_> {
+    pub fn descr(&self) -> &'static str {
+        match self {
+            ImplItemKind::Const(..) => "associated constant",
+            ImplItemKind::Fn(..) => "method",
+            ImplItemKind::Type(..) => "associated type",
+        }
+    }
+}
+
 /// The name of the associated type for `Fn` return types.
 pub const FN_OUTPUT_NAME: Symbol = sym::Output;
 
@@ -2516,6 +3474,61 @@ pub fn as_generic_param(&self) -> Option<(DefId, Ident)> {
         }
     }
 
+    /// The trait bounds written directly on a trait object type, e.g. `Foo` and `Send` in
+    /// `dyn Foo + Send`. The lifetime bound of the object is not included.
+    ///
+    /// Returns `None` for `impl Trait` (`TyKind::OpaqueDef`), whose bounds live on the
+    /// opaque type's item and need the crate to be looked up, and for all other types.
+    pub fn bounds(&self) -> Option<&'hir [PolyTraitRef<'hir>]> {
+        match self.kind {
+            TyKind::TraitObject(bounds, ..) => Some(bounds),
+            _ => None,
+        }
+    }
+
+    /// The span of the whole type, including the lifetime bound of a trait object such as
+    /// `dyn Foo + 'a`.
+    ///
+    /// The bound isn't tracked separately: lowering already gives trait objects the span of
+    /// the whole `dyn Foo + 'a`, so this is `self.span`.
+    pub fn full_span(&self) -> Span {
+        self.span
+    }
+
+    /// The `DefId` that a resolved type path like `Vec<u8>` refers to, if any.
+    pub fn as_path_def_id(&self) -> Option<DefId> {
+        match self.kind {
+            TyKind::Path(QPath::Resolved(_, path)) => path.res.opt_def_id(),
+            _ => None,
+        }
+    }
+
+    /// The generic arguments of the last segment of a resolved type path, like `<u8>` in
+    /// `std::vec::Vec<u8>`.
+    pub fn generic_args(&self) -> Option<&'hir GenericArgs<'hir>> {
+        match self.kind {
+            TyKind::Path(QPath::Resolved(_, path)) => path.segments.last()?.args,
+            _ => None,
+        }
+    }
+
+    /// The segments of a resolved type path like `std::vec::Vec<u8>`, if this is one.
+    pub fn path_segments(&self) -> Option<&'hir [PathSegment<'hir>]> {
+        match self.kind {
+            TyKind::Path(QPath::Resolved(_, path)) => Some(path.segments),
+            _ => None,
+        }
+    }
+
+    /// If this is an `impl Trait` type, returns the `ItemId` of its opaque type definition
+    /// and the generic arguments it is instantiated with.
+    pub fn as_opaque_def(&self) -> Option<(ItemId, &'hir [GenericArg<'hir>])> {
+        match self.kind {
+            TyKind::OpaqueDef(item_id, args, _) => Some((item_id, args)),
+            _ => None,
+        }
+    }
+
     pub fn peel_refs(&self) -> &Self {
         let mut final_ty = self;
         while let TyKind::Ref(_, MutTy { ty, .. }) = &final_ty.kind {
@@ -2524,6 +3537,14 @@ pub fn peel_refs(&self) -> &Self {
         final_ty
     }
 
+    /// If this is a reference type `&'a mut T`, returns its lifetime, referent and mutability.
+    pub fn as_ref(&self) -> Option<(&'hir Lifetime, &'hir Ty<'hir>, Mutability)> {
+        match self.kind {
+            TyKind::Ref(lifetime, MutTy { ty, mutbl }) => Some((lifetime, ty, mutbl)),
+            _ => None,
+        }
+    }
+
     pub fn find_self_aliases(&self) -> Vec<Span> {
         use crate::intravisit::Visitor;
         struct MyVisitor(Vec<Span>);
@@ -2547,6 +3568,78 @@ fn visit_ty(&mut self, t: &'v Ty<'v>) {
         my_visitor.visit_ty(self);
         my_visitor.0
     }
+
+    fn walk_(&self, it: &mut impl FnMut(&Ty<'hir>) -> bool) {
+        if !it(self) {
+            return;
+        }
+
+        match self.kind {
+            TyKind::Slice(ty)
+            | TyKind::Array(ty, _)
+            | TyKind::Ptr(MutTy { ty, .. })
+            | TyKind::Ref(_, MutTy { ty, .. }) => ty.walk_(it),
+            TyKind::Tup(tys) => tys.iter().for_each(|ty| ty.walk_(it)),
+            TyKind::BareFn(bare_fn) => {
+                bare_fn.decl.inputs.iter().for_each(|ty| ty.walk_(it));
+                if let FnRetTy::Return(ty) = bare_fn.decl.output {
+                    ty.walk_(it);
+                }
+            }
+            TyKind::TraitObject(bounds, ..) => {
+                let segments = bounds.iter().flat_map(|bound| bound.trait_ref.path.segments);
+                for args in segments.filter_map(|segment| segment.args) {
+                    for arg in args.args {
+                        if let GenericArg::Type(ty) = arg {
+                            ty.walk_(it);
+                        }
+                    }
+                    for binding in args.bindings {
+                        if let TypeBindingKind::Equality { term: Term::Ty(ty) } = binding.kind {
+                            ty.walk_(it);
+                        }
+                    }
+                }
+            }
+            TyKind::Never
+            | TyKind::Path(_)
+            | TyKind::OpaqueDef(..)
+            | TyKind::Typeof(_)
+            | TyKind::Infer
+            | TyKind::Err(_) => {}
+        }
+    }
+
+    /// Walk the type in pre-order: the element types of slices and arrays, the pointees of
+    /// raw pointers and references, tuple members, the inputs and output of function
+    /// pointers and the generic arguments of trait object bounds. Paths are not resolved and
+    /// their generic arguments are not visited.
+    ///
+    /// If `it(ty)` returns `false`, the children are not visited.
+    pub fn walk(&self, mut it: impl FnMut(&Ty<'hir>) -> bool) {
+        self.walk_(&mut it)
+    }
+
+    /// Whether this type mentions `Self` anywhere, e.g. `Self`, `Self::Item` or `Vec<Self>`.
+    pub fn contains_self_ty(&self) -> bool {
+        use crate::intravisit::Visitor;
+        struct MyVisitor(bool);
+        impl<'v> Visitor<'v> for MyVisitor {
+            fn visit_path_segment(&mut self, segment: &'v PathSegment<'v>) {
+                if segment.ident.name == kw::SelfUpper
+                    || matches!(segment.res, Res::SelfTyParam { .. } | Res::SelfTyAlias { .. })
+                {
+                    self.0 = true;
+                    return;
+                }
+                crate::intravisit::walk_path_segment(self, segment);
+            }
+        }
+
+        let mut my_visitor = MyVisitor(false);
+        my_visitor.visit_ty(self);
+        my_visitor.0
+    }
 }
 
 /// Not represented directly in the AST; referred to by name through a `ty_path`.
@@ -2709,6 +3802,26 @@ pub enum TyKind<'hir> {
     Err(rustc_span::ErrorGuaranteed),
 }
 
+impl TyKind<'_> {
+    pub fn descr(&self) -> &'static str {
+        match self {
+            TyKind::Slice(..) => "slice",
+            TyKind::Array(..) => "array",
+            TyKind::Ptr(..) => "raw pointer",
+            TyKind::Ref(..) => "reference",
+            TyKind::BareFn(..) => "function pointer",
+            TyKind::Never => "never type",
+            TyKind::Tup(..) => "tuple",
+            TyKind::Path(..) => "path",
+            TyKind::OpaqueDef(..) => "opaque type",
+            TyKind::TraitObject(..) => "trait object",
+            TyKind::Typeof(..) => "`typeof` type",
+            TyKind::Infer => "inferred type",
+            TyKind::Err(_) => "type error",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
 pub enum InlineAsmOperand<'hir> {
     In {
@@ -2922,6 +4035,14 @@ pub struct Variant<'hir> {
     pub span: Span,
 }
 
+impl Variant<'_> {
+    /// The kind of this variant's constructor, or `None` for struct variants.
+    #[inline]
+    pub fn ctor_kind(&self) -> Option<CtorKind> {
+        self.data.ctor_kind()
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug, HashStable_Generic)]
 pub enum UseKind {
     /// One import, e.g., `use foo::bar` or `use foo::bar as baz`.
@@ -3269,6 +4390,11 @@ pub fn is_const(&self) -> bool {
     pub fn is_unsafe(&self) -> bool {
         matches!(&self.unsafety, Unsafety::Unsafe)
     }
+
+    /// The quoted ABI spelling (e.g. `"C"`) for non-Rust ABIs, or `None` for `Abi::Rust`.
+    pub fn abi_str(&self) -> Option<String> {
+        if self.abi == Abi::Rust { None } else { Some(self.abi.to_string()) }
+    }
 }
 
 #[derive(Debug, Clone, Copy, HashStable_Generic)]
@@ -3389,6 +4515,13 @@ pub struct TraitItemRef {
     pub span: Span,
 }
 
+impl TraitItemRef {
+    /// Looks up the trait item this reference points to.
+    pub fn resolve<'hir>(&self, krate: &Crate<'hir>) -> &'hir TraitItem<'hir> {
+        krate.trait_item(self.id)
+    }
+}
+
 /// A reference from an impl to one of its associated items. This
 /// contains the item's ID, naturally, but also the item's name and
 /// some other high-level details (like whether it is an associated
@@ -3405,6 +4538,13 @@ pub struct ImplItemRef {
     pub trait_item_def_id: Option<DefId>,
 }
 
+impl ImplItemRef {
+    /// Looks up the impl item this reference points to.
+    pub fn resolve<'hir>(&self, krate: &Crate<'hir>) -> &'hir ImplItem<'hir> {
+        krate.impl_item(self.id)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug, HashStable_Generic)]
 pub enum AssocItemKind {
     Const,
@@ -3450,7 +4590,7 @@ pub struct ForeignItem<'hir> {
     pub vis_span: Span,
 }
 
-impl ForeignItem<'_> {
+impl<'hir> ForeignItem<'hir> {
     #[inline]
     pub fn hir_id(&self) -> HirId {
         // Items are always HIR owners.
@@ -3460,6 +4600,20 @@ pub fn hir_id(&self) -> HirId {
     pub fn foreign_item_id(&self) -> ForeignItemId {
         ForeignItemId { owner_id: self.owner_id }
     }
+
+    pub fn as_fn(&self) -> Option<(&'hir FnDecl<'hir>, &'hir [Ident], &'hir Generics<'hir>)> {
+        match self.kind {
+            ForeignItemKind::Fn(decl, param_names, generics) => Some((decl, param_names, generics)),
+            _ => None,
+        }
+    }
+
+    pub fn as_static(&self) -> Option<(&'hir Ty<'hir>, Mutability)> {
+        match self.kind {
+            ForeignItemKind::Static(ty, mutbl) => Some((ty, mutbl)),
+            _ => None,
+        }
+    }
 }
 
 /// An item within an `extern` block.
@@ -4048,3 +5202,6 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
     DebugFn(f)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,2052 @@
+use super::*;
+
+use crate::def::CtorOf;
+use rustc_ast::tokenstream::{DelimSpan, TokenStream};
+use rustc_arena::DroplessArena;
+use rustc_ast::DelimArgs;
+use rustc_span::create_default_session_globals_then;
+use rustc_span::def_id::{CrateNum, DefIndex, CRATE_DEF_ID};
+
+fn lifetime(name: &str, res: LifetimeName) -> Lifetime {
+    Lifetime { hir_id: HirId::INVALID, ident: Ident::from_str(name), res }
+}
+
+#[test]
+fn lifetime_refers_to_same_param() {
+    create_default_session_globals_then(|| {
+        let a1 = lifetime("'a", LifetimeName::Param(CRATE_DEF_ID));
+        let a2 = lifetime("'a", LifetimeName::Param(CRATE_DEF_ID));
+        let b = lifetime("'b", LifetimeName::Param(CRATE_DEF_ID));
+        assert!(a1.refers_to_same_param(&a2));
+        assert!(!a1.refers_to_same_param(&b));
+
+        let static1 = lifetime("'static", LifetimeName::Static);
+        let static2 = lifetime("'static", LifetimeName::Static);
+        assert!(static1.refers_to_same_param(&static2));
+        assert!(!static1.refers_to_same_param(&a1));
+
+        let anon1 = lifetime("'_", LifetimeName::Param(CRATE_DEF_ID));
+        let anon2 = lifetime("'_", LifetimeName::Param(CRATE_DEF_ID));
+        assert!(!anon1.refers_to_same_param(&anon2));
+        let infer = lifetime("'_", LifetimeName::Infer);
+        assert!(!infer.refers_to_same_param(&infer));
+    })
+}
+
+fn expr<'hir>(local_id: u32, kind: ExprKind<'hir>) -> Expr<'hir> {
+    let hir_id = HirId { owner: CRATE_OWNER_ID, local_id: ItemLocalId::from_u32(local_id) };
+    Expr { hir_id, kind, span: DUMMY_SP }
+}
+
+fn unit_expr<'hir>(local_id: u32) -> Expr<'hir> {
+    expr(local_id, ExprKind::Tup(&[]))
+}
+
+fn owner_info<'hir>(node: Option<Node<'hir>>, bodies: &[&'hir Body<'hir>]) -> OwnerInfo<'hir> {
+    OwnerInfo {
+        nodes: OwnerNodes {
+            opt_hash_including_bodies: None,
+            nodes: node
+                .into_iter()
+                .map(|node| Some(ParentedNode { parent: ItemLocalId::INVALID, node }))
+                .collect(),
+            bodies: bodies.iter().map(|body| (body.value.hir_id.local_id, *body)).collect(),
+        },
+        parenting: Default::default(),
+        attrs: AttributeMap { map: SortedMap::new(), opt_hash: None },
+        trait_map: Default::default(),
+    }
+}
+
+fn owner_id(index: u32) -> OwnerId {
+    OwnerId { def_id: LocalDefId { local_def_index: DefIndex::from_u32(index) } }
+}
+
+/// Builds a crate whose owners are the given infos, at the `LocalDefId`s `1..`.
+fn krate<'hir>(infos: &[&'hir OwnerInfo<'hir>]) -> Crate<'hir> {
+    let owners = std::iter::once(MaybeOwner::Phantom)
+        .chain(infos.iter().map(|&info| MaybeOwner::Owner(info)))
+        .collect();
+    Crate { owners, opt_hir_hash: None }
+}
+
+#[test]
+fn crate_par_for_each_body() {
+    use rustc_data_structures::fx::FxHashSet;
+    use rustc_data_structures::sync::Lock;
+
+    let (value1, value2) = (unit_expr(1), unit_expr(2));
+    let bodies = [
+        Body { params: &[], value: &value1, generator_kind: None },
+        Body { params: &[], value: &value2, generator_kind: None },
+    ];
+    let info = owner_info(None, &[&bodies[0], &bodies[1]]);
+    let krate = Crate {
+        owners: IndexVec::from_raw(vec![MaybeOwner::Owner(&info), MaybeOwner::Phantom]),
+        opt_hir_hash: None,
+    };
+
+    let seen = Lock::new(FxHashSet::default());
+    krate.par_for_each_body(|id, body| {
+        assert_eq!(id, body.id());
+        seen.lock().insert(id);
+    });
+    let expected: FxHashSet<_> = bodies.iter().map(|body| body.id()).collect();
+    assert_eq!(seen.into_inner(), expected);
+}
+
+#[test]
+fn crate_expr_kind_histogram() {
+    create_default_session_globals_then(|| {
+        // `(1, 2)` and `[()]`
+        let (one, two) = (int_lit(1), int_lit(2));
+        let elems = [expr(1, ExprKind::Lit(&one)), expr(2, ExprKind::Lit(&two))];
+        let tuple = expr(3, ExprKind::Tup(&elems));
+        let units = [unit_expr(4)];
+        let array = expr(5, ExprKind::Array(&units));
+        let bodies = [
+            Body { params: &[], value: &tuple, generator_kind: None },
+            Body { params: &[], value: &array, generator_kind: None },
+        ];
+        let info = owner_info(None, &[&bodies[0], &bodies[1]]);
+        let krate = krate(&[&info]);
+
+        let expected = BTreeMap::from([("Array", 1), ("Lit", 2), ("Tup", 2)]);
+        assert_eq!(krate.expr_kind_histogram(), expected);
+    })
+}
+
+fn ty<'hir>(kind: TyKind<'hir>) -> Ty<'hir> {
+    Ty { hir_id: HirId::INVALID, kind, span: DUMMY_SP }
+}
+
+fn path<'hir>(res: Res, segments: &'hir [PathSegment<'hir>]) -> Path<'hir> {
+    Path { span: DUMMY_SP, res, segments }
+}
+
+fn segment<'hir>(name: &str, res: Res) -> PathSegment<'hir> {
+    PathSegment::new(Ident::from_str(name), HirId::INVALID, res)
+}
+
+/// The single-segment path `name`, resolved to `res`.
+fn simple_path<'hir>(arena: &'hir DroplessArena, name: &str, res: Res) -> &'hir Path<'hir> {
+    let segments = arena.alloc_slice(&[segment(name, res)]);
+    arena.alloc(path(res, segments))
+}
+
+fn path_expr<'hir>(arena: &'hir DroplessArena, local_id: u32, name: &str, res: Res) -> Expr<'hir> {
+    expr(local_id, ExprKind::Path(QPath::Resolved(None, simple_path(arena, name, res))))
+}
+
+fn path_ty<'hir>(arena: &'hir DroplessArena, name: &str, res: Res) -> Ty<'hir> {
+    ty(TyKind::Path(QPath::Resolved(None, simple_path(arena, name, res))))
+}
+
+fn fn_header(abi: Abi) -> FnHeader {
+    FnHeader {
+        unsafety: Unsafety::Normal,
+        constness: Constness::NotConst,
+        asyncness: IsAsync::NotAsync,
+        abi,
+    }
+}
+
+/// The declaration of a function without a return type, like `fn f(..)`.
+fn fn_decl<'hir>(inputs: &'hir [Ty<'hir>]) -> FnDecl<'hir> {
+    FnDecl {
+        inputs,
+        output: FnRetTy::DefaultReturn(DUMMY_SP),
+        c_variadic: false,
+        implicit_self: ImplicitSelfKind::None,
+        lifetime_elision_allowed: false,
+    }
+}
+
+fn fn_sig<'hir>(decl: &'hir FnDecl<'hir>) -> FnSig<'hir> {
+    FnSig { header: fn_header(Abi::Rust), decl, span: DUMMY_SP }
+}
+
+#[test]
+fn path_res_def_id() {
+    create_default_session_globals_then(|| {
+        // `foo::bar`, resolved to a function.
+        let fn_def_id = owner_id(1).to_def_id();
+        let fn_res = Res::Def(DefKind::Fn, fn_def_id);
+        let segments = [segment("foo", Res::Err), segment("bar", fn_res)];
+        let resolved = path(fn_res, &segments);
+        assert_eq!(resolved.res_def_id(), Some(fn_def_id));
+        assert!(!resolved.is_empty());
+        assert_eq!(resolved.last_segment().unwrap().ident.name.as_str(), "bar");
+
+        let segments = [segment("missing", Res::Err)];
+        let unresolved = path(Res::Err, &segments);
+        assert_eq!(unresolved.res_def_id(), None);
+        assert_eq!(unresolved.last_segment().unwrap().ident.name.as_str(), "missing");
+
+        let empty = path(Res::Err, &[]);
+        assert!(empty.is_empty());
+        assert!(empty.last_segment().is_none());
+    })
+}
+
+#[test]
+fn qpath_qself_ty() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let t = Ty { hir_id: local_hir_id(1), span: span(1, 2), ..path_ty(&arena, "T", Res::Err) };
+        let vec_args = GenericArgs { args: &[GenericArg::Type(&t)], ..GenericArgs::none() };
+        let vec_segments = [PathSegment { args: Some(&vec_args), ..segment("Vec", Res::Err) }];
+        let vec_path = path(Res::Err, &vec_segments);
+        let vec_t =
+            Ty { hir_id: local_hir_id(2), ..ty(TyKind::Path(QPath::Resolved(None, &vec_path))) };
+
+        // `<Vec<T> as Clone>::clone`
+        let clone_segments = [segment("Clone", Res::Err), segment("clone", Res::Err)];
+        let clone_path = path(Res::Err, &clone_segments);
+        let qualified = QPath::Resolved(Some(&vec_t), &clone_path);
+        assert_eq!(qualified.qself_ty().map(|qself| qself.hir_id), Some(local_hir_id(2)));
+
+        // `Clone::clone`
+        assert!(QPath::Resolved(None, &clone_path).qself_ty().is_none());
+
+        // `<T>::default`
+        let default = PathSegment {
+            ident: Ident::new(Symbol::intern("default"), span(5, 12)),
+            ..segment("default", Res::Err)
+        };
+        let type_relative = QPath::TypeRelative(&t, &default);
+        assert_eq!(type_relative.qself_ty().map(|qself| qself.hir_id), Some(local_hir_id(1)));
+        assert_eq!(type_relative.span(), span(1, 12));
+    })
+}
+
+#[test]
+fn ty_contains_self_ty() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let self_res = Res::SelfTyParam { trait_: CRATE_DEF_ID.to_def_id() };
+        let self_ty = path_ty(&arena, "Self", self_res);
+        assert!(self_ty.contains_self_ty());
+
+        // `Self::Item`
+        let item_segment = segment("Item", Res::Err);
+        let assoc_ty = ty(TyKind::Path(QPath::TypeRelative(&self_ty, &item_segment)));
+        assert!(assoc_ty.contains_self_ty());
+
+        // `Vec<Self>`
+        let vec_res = Res::Def(DefKind::Struct, CRATE_DEF_ID.to_def_id());
+        let vec_args = GenericArgs { args: &[GenericArg::Type(&self_ty)], ..GenericArgs::none() };
+        let vec_segments = [PathSegment { args: Some(&vec_args), ..segment("Vec", vec_res) }];
+        let vec_path = path(vec_res, &vec_segments);
+        let vec_ty = ty(TyKind::Path(QPath::Resolved(None, &vec_path)));
+        assert!(vec_ty.contains_self_ty());
+
+        // `u8`
+        let u8_res = Res::PrimTy(PrimTy::Uint(UintTy::U8));
+        let u8_ty = path_ty(&arena, "u8", u8_res);
+        assert!(!u8_ty.contains_self_ty());
+    })
+}
+
+fn pat<'hir>(kind: PatKind<'hir>) -> Pat<'hir> {
+    Pat { hir_id: HirId::INVALID, kind, span: DUMMY_SP, default_binding_modes: true }
+}
+
+fn local<'hir>(
+    pat: &'hir Pat<'hir>,
+    init: Option<&'hir Expr<'hir>>,
+    source: LocalSource,
+) -> Local<'hir> {
+    Local { pat, ty: None, init, els: None, hir_id: HirId::INVALID, span: DUMMY_SP, source }
+}
+
+#[test]
+fn stmt_kind_descr() {
+    let wild = pat(PatKind::Wild);
+    let local = local(&wild, None, LocalSource::Normal);
+    let unit = unit_expr(1);
+    assert_eq!(StmtKind::Local(&local).descr(), "local binding");
+    assert_eq!(StmtKind::Item(ItemId { owner_id: CRATE_OWNER_ID }).descr(), "item");
+    assert_eq!(StmtKind::Expr(&unit).descr(), "expression");
+    assert_eq!(StmtKind::Semi(&unit).descr(), "expression statement");
+}
+
+#[test]
+fn stmt_kind_predicates() {
+    let wild = pat(PatKind::Wild);
+    let local = local(&wild, None, LocalSource::Normal);
+    let unit = unit_expr(1);
+    let (expr, semi) = (StmtKind::Expr(&unit), StmtKind::Semi(&unit));
+    assert!(expr.is_expr() && semi.is_expr());
+    assert_ne!(expr.descr(), semi.descr());
+    assert!(!expr.is_local() && !expr.is_item());
+
+    let local = StmtKind::Local(&local);
+    assert!(local.is_local() && !local.is_expr());
+    let item = StmtKind::Item(ItemId { owner_id: CRATE_OWNER_ID });
+    assert!(item.is_item() && !item.is_local() && !item.is_expr());
+}
+
+#[test]
+fn stmt_full_span() {
+    create_default_session_globals_then(|| {
+        // `foo()` and `foo();`
+        let call = Expr { span: span(0, 5), ..unit_expr(1) };
+        let stmt = |kind, sp| Stmt { hir_id: HirId::INVALID, kind, span: sp };
+        let expr_stmt = stmt(StmtKind::Expr(&call), span(0, 5));
+        assert_eq!(expr_stmt.full_span(), call.span);
+        let semi_stmt = stmt(StmtKind::Semi(&call), span(0, 6));
+        assert_eq!(semi_stmt.full_span(), span(0, 6));
+        assert!(semi_stmt.full_span().contains(call.span));
+    })
+}
+
+#[test]
+fn expr_kind_descr() {
+    create_default_session_globals_then(|| {
+        let unit = unit_expr(1);
+        assert_eq!(unit.kind.descr(), "tuple");
+        let clone = segment("clone", Res::Err);
+        let method_call = expr(2, ExprKind::MethodCall(&clone, &unit, &[], DUMMY_SP));
+        assert_eq!(method_call.kind.descr(), "method call");
+        assert_eq!(ExprKind::Call(&unit, &[]).descr(), "function call");
+        assert_eq!(ExprKind::Ret(None).descr(), "`return` expression");
+
+        // Desugared nodes are described by what they wrap.
+        assert_eq!(ExprKind::DropTemps(&method_call).descr(), "method call");
+    })
+}
+
+fn block<'hir>(stmts: &'hir [Stmt<'hir>], expr: Option<&'hir Expr<'hir>>) -> Block<'hir> {
+    Block {
+        stmts,
+        expr,
+        hir_id: HirId::INVALID,
+        rules: BlockCheckMode::DefaultBlock,
+        span: DUMMY_SP,
+        targeted_by_break: false,
+    }
+}
+
+fn semi<'hir>(expr: &'hir Expr<'hir>) -> Stmt<'hir> {
+    Stmt { hir_id: HirId::INVALID, kind: StmtKind::Semi(expr), span: DUMMY_SP }
+}
+
+#[test]
+fn expr_block_diverges() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let x = path_expr(&arena, 1, "x", Res::Local(HirId::INVALID));
+
+        // `{ return x; }`
+        let ret = expr(2, ExprKind::Ret(Some(&x)));
+        let ret_stmts = [semi(&ret)];
+        let ret_block = block(&ret_stmts, None);
+        assert!(expr(3, ExprKind::Block(&ret_block, None)).block_diverges());
+
+        // `{ foo(); }`
+        let foo_res = Res::Def(DefKind::Fn, CRATE_DEF_ID.to_def_id());
+        let foo = path_expr(&arena, 4, "foo", foo_res);
+        let call = expr(5, ExprKind::Call(&foo, &[]));
+        let call_stmts = [semi(&call)];
+        let call_block = block(&call_stmts, None);
+        assert!(!expr(6, ExprKind::Block(&call_block, None)).block_diverges());
+
+        assert!(!ret.block_diverges());
+    })
+}
+
+#[test]
+fn impl_item_ref_resolve() {
+    create_default_session_globals_then(|| {
+        let unit = ty(TyKind::Tup(&[]));
+        let impl_item = ImplItem {
+            ident: Ident::from_str("Assoc"),
+            owner_id: owner_id(1),
+            generics: Generics::empty(),
+            kind: ImplItemKind::Type(&unit),
+            defaultness: Defaultness::Final,
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let info = owner_info(Some(Node::ImplItem(&impl_item)), &[]);
+        let krate = krate(&[&info]);
+
+        let item_ref = ImplItemRef {
+            id: ImplItemId { owner_id: owner_id(1) },
+            ident: impl_item.ident,
+            kind: AssocItemKind::Type,
+            span: DUMMY_SP,
+            trait_item_def_id: None,
+        };
+        let resolved = item_ref.resolve(&krate);
+        assert_eq!(resolved.owner_id, owner_id(1));
+        assert_eq!(resolved.ident, impl_item.ident);
+    })
+}
+
+fn local_hir_id(local_id: u32) -> HirId {
+    HirId { owner: CRATE_OWNER_ID, local_id: ItemLocalId::from_u32(local_id) }
+}
+
+#[test]
+fn expr_referenced_locals() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let (a_id, b_id) = (local_hir_id(10), local_hir_id(11));
+        let a = path_expr(&arena, 1, "a", Res::Local(a_id));
+        let b = path_expr(&arena, 2, "b", Res::Local(b_id));
+
+        // `a + b`
+        let add = Spanned { node: BinOpKind::Add, span: DUMMY_SP };
+        let sum = expr(3, ExprKind::Binary(add, &a, &b));
+        assert_eq!(sum.referenced_locals(), vec![a_id, b_id]);
+        assert_eq!(unit_expr(4).referenced_locals(), vec![]);
+    })
+}
+
+#[test]
+fn fn_header_abi_str() {
+    assert_eq!(fn_header(Abi::C { unwind: false }).abi_str().as_deref(), Some("\"C\""));
+    assert_eq!(fn_header(Abi::Rust).abi_str(), None);
+}
+
+#[test]
+fn pat_as_path() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let none_res =
+            Res::Def(DefKind::Ctor(CtorOf::Variant, CtorKind::Const), CRATE_DEF_ID.to_def_id());
+        let none_path = simple_path(&arena, "None", none_res);
+        let none = pat(PatKind::Path(QPath::Resolved(None, none_path)));
+        assert!(matches!(none.as_path(), Some(QPath::Resolved(None, p)) if p.res == none_res));
+
+        let x = Ident::from_str("x");
+        let binding = pat(PatKind::Binding(BindingAnnotation::NONE, HirId::INVALID, x, None));
+        assert!(binding.as_path().is_none());
+    })
+}
+
+fn variant<'hir>(name: &str, data: VariantData<'hir>) -> Variant<'hir> {
+    Variant {
+        ident: Ident::from_str(name),
+        hir_id: HirId::INVALID,
+        def_id: CRATE_DEF_ID,
+        data,
+        disr_expr: None,
+        span: DUMMY_SP,
+    }
+}
+
+#[test]
+fn variant_ctor_kind() {
+    create_default_session_globals_then(|| {
+        let tuple = variant("Tuple", VariantData::Tuple(&[], HirId::INVALID, CRATE_DEF_ID));
+        assert_eq!(tuple.ctor_kind(), Some(CtorKind::Fn));
+        let unit = variant("Unit", VariantData::Unit(HirId::INVALID, CRATE_DEF_ID));
+        assert_eq!(unit.ctor_kind(), Some(CtorKind::Const));
+        let strukt = variant("Struct", VariantData::Struct(&[], false));
+        assert_eq!(strukt.ctor_kind(), None);
+    })
+}
+
+fn int_lit(value: u128) -> Lit {
+    Spanned { node: LitKind::Int(value, ast::LitIntType::Unsuffixed), span: DUMMY_SP }
+}
+
+#[test]
+fn expr_is_const_candidate() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let (one, two) = (int_lit(1), int_lit(2));
+        let (one, two) = (expr(1, ExprKind::Lit(&one)), expr(2, ExprKind::Lit(&two)));
+
+        // `1 + 2`
+        let add = Spanned { node: BinOpKind::Add, span: DUMMY_SP };
+        assert!(expr(3, ExprKind::Binary(add, &one, &two)).is_const_candidate());
+
+        // `[1, 2]`
+        let elems = [one, two];
+        assert!(expr(4, ExprKind::Array(&elems)).is_const_candidate());
+
+        // `foo()`
+        let foo_res = Res::Def(DefKind::Fn, CRATE_DEF_ID.to_def_id());
+        let foo = path_expr(&arena, 5, "foo", foo_res);
+        assert!(!foo.is_const_candidate());
+        assert!(!expr(6, ExprKind::Call(&foo, &[])).is_const_candidate());
+    })
+}
+
+#[test]
+fn ty_as_path_def_id() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let vec_def_id = DefId { krate: CrateNum::from_u32(1), index: DefIndex::from_u32(7) };
+        let vec_res = Res::Def(DefKind::Struct, vec_def_id);
+
+        // `Vec<u8>`
+        let u8_res = Res::PrimTy(PrimTy::Uint(UintTy::U8));
+        let u8_ty = path_ty(&arena, "u8", u8_res);
+        let vec_args = GenericArgs { args: &[GenericArg::Type(&u8_ty)], ..GenericArgs::none() };
+        let vec_segments = [PathSegment { args: Some(&vec_args), ..segment("Vec", vec_res) }];
+        let vec_path = path(vec_res, &vec_segments);
+        let vec_ty = ty(TyKind::Path(QPath::Resolved(None, &vec_path)));
+        assert_eq!(vec_ty.as_path_def_id(), Some(vec_def_id));
+
+        // `dyn Foo`
+        let foo_res = Res::Def(DefKind::Trait, CRATE_DEF_ID.to_def_id());
+        let foo_path = simple_path(&arena, "Foo", foo_res);
+        let foo_ref = PolyTraitRef {
+            bound_generic_params: &[],
+            trait_ref: TraitRef { path: foo_path, hir_ref_id: HirId::INVALID },
+            span: DUMMY_SP,
+        };
+        let elided = lifetime("", LifetimeName::ImplicitObjectLifetimeDefault);
+        let bounds = [foo_ref];
+        let dyn_ty = ty(TyKind::TraitObject(&bounds, &elided, TraitObjectSyntax::Dyn));
+        assert_eq!(dyn_ty.as_path_def_id(), None);
+    })
+}
+
+#[test]
+fn crate_macro_by_name() {
+    create_default_session_globals_then(|| {
+        let body = DelimArgs {
+            dspan: DelimSpan::dummy(),
+            delim: ast::MacDelimiter::Brace,
+            tokens: TokenStream::default(),
+        };
+        let macro_def = ast::MacroDef { body: ast::ptr::P(body), macro_rules: true };
+        let item = Item {
+            ident: Ident::from_str("foo"),
+            owner_id: owner_id(1),
+            kind: ItemKind::Macro(&macro_def, MacroKind::Bang),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let info = owner_info(Some(Node::Item(&item)), &[]);
+        let krate = krate(&[&info]);
+
+        let (found, found_def) = krate.macro_by_name(Symbol::intern("foo")).unwrap();
+        assert_eq!(found.owner_id, owner_id(1));
+        assert!(std::ptr::eq(found_def, &macro_def));
+        assert!(krate.macro_by_name(Symbol::intern("bar")).is_none());
+    })
+}
+
+#[test]
+fn expr_walk() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let a = path_expr(&arena, 0, "a", Res::Err);
+        let b = path_expr(&arena, 1, "b", Res::Err);
+        let c = path_expr(&arena, 2, "c", Res::Err);
+
+        // `a + b * c`
+        let add = Spanned { node: BinOpKind::Add, span: DUMMY_SP };
+        let mul = Spanned { node: BinOpKind::Mul, span: DUMMY_SP };
+        let product = expr(3, ExprKind::Binary(mul, &b, &c));
+        let sum = expr(4, ExprKind::Binary(add, &a, &product));
+
+        let mut count = 0;
+        sum.walk_always(|_| count += 1);
+        assert_eq!(count, 5);
+
+        let mut visited = vec![];
+        sum.walk(|e| {
+            visited.push(e.hir_id.local_id.as_u32());
+            !matches!(e.kind, ExprKind::Binary(Spanned { node: BinOpKind::Mul, .. }, ..))
+        });
+        assert_eq!(visited, vec![4, 0, 3]);
+    })
+}
+
+fn walk_order(expr: &Expr<'_>) -> Vec<u32> {
+    let mut visited = vec![];
+    expr.walk_always(|e| visited.push(e.hir_id.local_id.as_u32()));
+    visited
+}
+
+#[test]
+fn expr_walk_nested() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let a = path_expr(&arena, 0, "a", Res::Err);
+        let b = path_expr(&arena, 1, "b", Res::Err);
+        let c = path_expr(&arena, 2, "c", Res::Err);
+
+        // `match a { _ if b => c }`
+        let wild = pat(PatKind::Wild);
+        let arms = [Arm {
+            hir_id: HirId::INVALID,
+            span: DUMMY_SP,
+            pat: &wild,
+            guard: Some(Guard::If(&b)),
+            body: &c,
+        }];
+        let match_ = expr(3, ExprKind::Match(&a, &arms, MatchSource::Normal));
+        assert_eq!(walk_order(&match_), [3, 0, 1, 2]);
+
+        // `S { x: a, ..b }`
+        let s_path = simple_path(&arena, "S", Res::Err);
+        let s_qpath = QPath::Resolved(None, s_path);
+        let fields = [ExprField {
+            hir_id: HirId::INVALID,
+            ident: Ident::from_str("x"),
+            expr: &a,
+            span: DUMMY_SP,
+            is_shorthand: false,
+        }];
+        let struct_ = expr(4, ExprKind::Struct(&s_qpath, &fields, Some(&b)));
+        assert_eq!(walk_order(&struct_), [4, 0, 1]);
+
+        // `[a, b]` and `(b, c)`
+        let elems = [a, b];
+        assert_eq!(walk_order(&expr(5, ExprKind::Array(&elems))), [5, 0, 1]);
+        let elems = [b, c];
+        assert_eq!(walk_order(&expr(6, ExprKind::Tup(&elems))), [6, 1, 2]);
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn generic_args_and_bindings_span() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `<u8, Item = bool>`
+        let u8_res = Res::PrimTy(PrimTy::Uint(UintTy::U8));
+        let u8_ty = Ty { span: span(1, 3), ..path_ty(&arena, "u8", u8_res) };
+        let bool_res = Res::PrimTy(PrimTy::Bool);
+        let bool_ty = Ty { span: span(12, 16), ..path_ty(&arena, "bool", bool_res) };
+        let item_binding = TypeBinding {
+            hir_id: HirId::INVALID,
+            ident: Ident::from_str("Item"),
+            gen_args: &GenericArgs::none(),
+            kind: TypeBindingKind::Equality { term: Term::Ty(&bool_ty) },
+            span: span(5, 16),
+        };
+        let args = GenericArgs {
+            args: &[GenericArg::Type(&u8_ty)],
+            bindings: std::slice::from_ref(&item_binding),
+            ..GenericArgs::none()
+        };
+        assert_eq!(args.args_and_bindings_span(), Some(span(1, 16)));
+        assert_eq!(GenericArgs::none().args_and_bindings_span(), None);
+    })
+}
+
+#[test]
+fn generic_args_kind_queries() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `<'a, T, 3>`
+        let a = lifetime("'a", LifetimeName::Param(CRATE_DEF_ID));
+        let t_res = Res::Def(DefKind::TyParam, owner_id(1).to_def_id());
+        let t_ty = path_ty(&arena, "T", t_res);
+        let three = ConstArg {
+            value: AnonConst {
+                hir_id: local_hir_id(1),
+                def_id: owner_id(2).def_id,
+                body: BodyId { hir_id: local_hir_id(2) },
+            },
+            span: DUMMY_SP,
+        };
+        let args = [GenericArg::Lifetime(&a), GenericArg::Type(&t_ty), GenericArg::Const(three)];
+        let foo_args = GenericArgs { args: &args, ..GenericArgs::none() };
+        assert!(foo_args.has_lifetime_params());
+        assert!(foo_args.has_type_params());
+        assert!(foo_args.has_const_params());
+        assert_eq!((foo_args.num_lifetime_params(), foo_args.num_type_params()), (1, 1));
+
+        let none = GenericArgs::none();
+        assert!(!none.has_lifetime_params() && !none.has_type_params() && !none.has_const_params());
+    })
+}
+
+fn foreign_item<'hir>(name: &str, kind: ForeignItemKind<'hir>) -> ForeignItem<'hir> {
+    ForeignItem {
+        ident: Ident::from_str(name),
+        kind,
+        owner_id: CRATE_OWNER_ID,
+        span: DUMMY_SP,
+        vis_span: DUMMY_SP,
+    }
+}
+
+#[test]
+fn foreign_item_accessors() {
+    create_default_session_globals_then(|| {
+        let decl = fn_decl(&[]);
+        let param_names = [Ident::from_str("x")];
+        let fun = foreign_item("f", ForeignItemKind::Fn(&decl, &param_names, Generics::empty()));
+        let (fn_decl, fn_param_names, _) = fun.as_fn().unwrap();
+        assert!(!fn_decl.c_variadic);
+        assert_eq!(fn_param_names, &param_names);
+        assert!(fun.as_static().is_none());
+
+        let unit = ty(TyKind::Tup(&[]));
+        let stat = foreign_item("S", ForeignItemKind::Static(&unit, Mutability::Mut));
+        assert!(matches!(stat.as_static(), Some((_, Mutability::Mut))));
+        assert!(stat.as_fn().is_none());
+    })
+}
+
+fn trait_item<'hir>(
+    index: u32,
+    name: &str,
+    is_auto: IsAuto,
+    unsafety: Unsafety,
+    items: &'hir [TraitItemRef],
+) -> Item<'hir> {
+    Item {
+        ident: Ident::from_str(name),
+        owner_id: owner_id(index),
+        kind: ItemKind::Trait(is_auto, unsafety, Generics::empty(), &[], items),
+        span: DUMMY_SP,
+        vis_span: DUMMY_SP,
+    }
+}
+
+#[test]
+fn crate_traits() {
+    create_default_session_globals_then(|| {
+        let auto_trait = trait_item(1, "Auto", IsAuto::Yes, Unsafety::Unsafe, &[]);
+        let method = TraitItemRef {
+            id: TraitItemId { owner_id: owner_id(3) },
+            ident: Ident::from_str("method"),
+            kind: AssocItemKind::Fn { has_self: true },
+            span: DUMMY_SP,
+        };
+        let normal_items = [method];
+        let normal_trait = trait_item(2, "Normal", IsAuto::No, Unsafety::Normal, &normal_items);
+        let auto_info = owner_info(Some(Node::Item(&auto_trait)), &[]);
+        let normal_info = owner_info(Some(Node::Item(&normal_trait)), &[]);
+        let krate = krate(&[&auto_info, &normal_info]);
+
+        let traits: Vec<_> = krate.traits().collect();
+        assert_eq!(traits.len(), 2);
+        let (item, is_auto, unsafety, items) = traits[0];
+        assert_eq!(item.ident.as_str(), "Auto");
+        assert_eq!((is_auto, unsafety), (IsAuto::Yes, Unsafety::Unsafe));
+        assert!(items.is_empty());
+        let (item, is_auto, unsafety, items) = traits[1];
+        assert_eq!(item.ident.as_str(), "Normal");
+        assert_eq!((is_auto, unsafety), (IsAuto::No, Unsafety::Normal));
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].ident.as_str(), "method");
+    })
+}
+
+#[test]
+fn pat_binding_ident() {
+    create_default_session_globals_then(|| {
+        let (x, y) = (Ident::from_str("x"), Ident::from_str("y"));
+
+        // `x`
+        let x_pat = pat(PatKind::Binding(BindingAnnotation::NONE, HirId::INVALID, x, None));
+        assert_eq!(x_pat.binding_ident(), Some(x));
+        assert_eq!(x_pat.simple_ident(), Some(x));
+
+        // `x @ y`
+        let y_pat = pat(PatKind::Binding(BindingAnnotation::NONE, HirId::INVALID, y, None));
+        let x_at_y =
+            pat(PatKind::Binding(BindingAnnotation::NONE, HirId::INVALID, x, Some(&y_pat)));
+        assert_eq!(x_at_y.binding_ident(), Some(x));
+        assert_eq!(x_at_y.simple_ident(), None);
+
+        assert_eq!(pat(PatKind::Wild).binding_ident(), None);
+    })
+}
+
+#[test]
+fn pat_simple_ident() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let x = Ident::from_str("x");
+        let binding = |annotation, sub| pat(PatKind::Binding(annotation, HirId::INVALID, x, sub));
+
+        // `x`
+        assert_eq!(binding(BindingAnnotation::NONE, None).simple_ident(), Some(x));
+
+        // `mut x` still binds by value, so it counts as simple.
+        assert_eq!(binding(BindingAnnotation::MUT, None).simple_ident(), Some(x));
+
+        // `ref x`
+        assert_eq!(binding(BindingAnnotation::REF, None).simple_ident(), None);
+
+        // `x @ Some(_)`
+        let some_path = simple_path(&arena, "Some", Res::Err);
+        let some = QPath::Resolved(None, some_path);
+        let wild = [pat(PatKind::Wild)];
+        let some_wild = pat(PatKind::TupleStruct(some, &wild, DotDotPos::new(None)));
+        assert_eq!(binding(BindingAnnotation::NONE, Some(&some_wild)).simple_ident(), None);
+    })
+}
+
+#[test]
+fn expr_control_flow_label() {
+    create_default_session_globals_then(|| {
+        let label = Label { ident: Ident::from_str("'a") };
+
+        // `break 'a`
+        let labeled = Destination { label: Some(label), target_id: Ok(HirId::INVALID) };
+        let brk = expr(1, ExprKind::Break(labeled, None));
+        assert_eq!(brk.control_flow_label().map(|label| label.ident), Some(label.ident));
+
+        // `continue`
+        let unlabeled = Destination { label: None, target_id: Ok(HirId::INVALID) };
+        assert!(expr(2, ExprKind::Continue(unlabeled)).control_flow_label().is_none());
+        assert!(unit_expr(3).control_flow_label().is_none());
+    })
+}
+
+fn poly_trait_ref<'hir>(path: &'hir Path<'hir>) -> PolyTraitRef<'hir> {
+    PolyTraitRef {
+        bound_generic_params: &[],
+        trait_ref: TraitRef { path, hir_ref_id: HirId::INVALID },
+        span: DUMMY_SP,
+    }
+}
+
+#[test]
+fn ty_bounds() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `dyn Foo + Send`
+        let foo_res = Res::Def(DefKind::Trait, owner_id(1).to_def_id());
+        let foo_path = simple_path(&arena, "Foo", foo_res);
+        let send_res = Res::Def(DefKind::Trait, owner_id(2).to_def_id());
+        let send_path = simple_path(&arena, "Send", send_res);
+        let bounds = [poly_trait_ref(foo_path), poly_trait_ref(send_path)];
+        let elided = lifetime("", LifetimeName::ImplicitObjectLifetimeDefault);
+        let dyn_ty = ty(TyKind::TraitObject(&bounds, &elided, TraitObjectSyntax::Dyn));
+
+        let trait_def_ids: Vec<_> =
+            dyn_ty.bounds().unwrap().iter().map(|bound| bound.trait_ref.trait_def_id()).collect();
+        assert_eq!(trait_def_ids, [foo_res.opt_def_id(), send_res.opt_def_id()]);
+        assert!(ty(TyKind::Never).bounds().is_none());
+    })
+}
+
+#[test]
+fn local_pat_and_init() {
+    let wild = pat(PatKind::Wild);
+    let unit = unit_expr(1);
+
+    // `let _ = ();`
+    let normal = local(&wild, Some(&unit), LocalSource::Normal);
+    let (pat, init) = normal.pat_and_init();
+    assert!(matches!(pat.kind, PatKind::Wild));
+    assert_eq!(init.map(|init| init.hir_id), Some(unit.hir_id));
+    assert!(!normal.source_is_desugared());
+
+    // The `let` introduced for the awaited future in `expr.await`.
+    let awaited = local(&wild, Some(&unit), LocalSource::AwaitDesugar);
+    assert!(awaited.source_is_desugared());
+    assert!(local(&wild, None, LocalSource::AssignDesugar(DUMMY_SP)).source_is_desugared());
+}
+
+#[test]
+fn expr_as_field() {
+    create_default_session_globals_then(|| {
+        let a = unit_expr(1);
+
+        // `a.b`
+        let named = expr(2, ExprKind::Field(&a, Ident::from_str("b")));
+        let (base, field) = named.as_field().unwrap();
+        assert_eq!(base.hir_id, a.hir_id);
+        assert_eq!(field.name.as_str(), "b");
+
+        // `a.0`
+        let positional = expr(3, ExprKind::Field(&a, Ident::from_str("0")));
+        assert_eq!(positional.as_field().map(|(_, field)| field.name.as_str()), Some("0"));
+        assert!(a.as_field().is_none());
+    })
+}
+
+#[test]
+fn expr_as_addr_of() {
+    create_default_session_globals_then(|| {
+        let (x, y) = (unit_expr(1), unit_expr(2));
+
+        // `&x`
+        let shared = expr(3, ExprKind::AddrOf(BorrowKind::Ref, Mutability::Not, &x));
+        let (kind, mutbl, referent) = shared.as_addr_of().unwrap();
+        assert_eq!((kind, mutbl, referent.hir_id), (BorrowKind::Ref, Mutability::Not, x.hir_id));
+
+        // `&mut y`
+        let unique = expr(4, ExprKind::AddrOf(BorrowKind::Ref, Mutability::Mut, &y));
+        let (_, mutbl, referent) = unique.as_addr_of().unwrap();
+        assert_eq!((mutbl, referent.hir_id), (Mutability::Mut, y.hir_id));
+        assert!(x.as_addr_of().is_none());
+    })
+}
+
+#[test]
+fn expr_as_match() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `match x { _ => () }`
+        let x = path_expr(&arena, 2, "x", Res::Local(local_hir_id(1)));
+        let wild = pat(PatKind::Wild);
+        let unit = unit_expr(3);
+        let arms =
+            [Arm { hir_id: HirId::INVALID, span: DUMMY_SP, pat: &wild, guard: None, body: &unit }];
+        let match_ = expr(4, ExprKind::Match(&x, &arms, MatchSource::Normal));
+
+        let (scrutinee, match_arms, source) = match_.as_match().unwrap();
+        assert_eq!(scrutinee.hir_id, x.hir_id);
+        assert_eq!(match_arms.len(), 1);
+        assert_eq!(match_arms[0].body.hir_id, unit.hir_id);
+        assert_eq!(source, MatchSource::Normal);
+        assert!(x.as_match().is_none());
+    })
+}
+
+#[test]
+fn expr_as_repeat() {
+    create_default_session_globals_then(|| {
+        // `[0; 4]`
+        let zero = int_lit(0);
+        let zero = expr(1, ExprKind::Lit(&zero));
+        let count = AnonConst {
+            hir_id: local_hir_id(2),
+            def_id: owner_id(2).def_id,
+            body: BodyId { hir_id: local_hir_id(3) },
+        };
+        let repeat = expr(4, ExprKind::Repeat(&zero, ArrayLen::Body(count)));
+        let (elem, len) = repeat.as_repeat().unwrap();
+        assert_eq!(elem.hir_id, zero.hir_id);
+        assert_eq!(len.body, count.body);
+
+        // `[0; _]`
+        let inferred = expr(5, ExprKind::Repeat(&zero, ArrayLen::Infer(local_hir_id(6), DUMMY_SP)));
+        assert!(inferred.as_repeat().is_none());
+        assert!(zero.as_repeat().is_none());
+    })
+}
+
+#[test]
+fn generic_param_kind_descr() {
+    let lifetime = GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit };
+    assert_eq!(lifetime.descr(), "lifetime");
+    assert_eq!(GenericParamKind::Type { default: None, synthetic: false }.descr(), "type");
+
+    let never = ty(TyKind::Never);
+    let constant = GenericParamKind::Const { ty: &never, default: None };
+    assert_eq!(constant.descr(), "const");
+}
+
+#[test]
+fn crate_find_inherent_method() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `impl Foo { fn foo() {} }`
+        let foo_def_id = owner_id(9).to_def_id();
+        let foo_res = Res::Def(DefKind::Struct, foo_def_id);
+        let foo_ty = path_ty(&arena, "Foo", foo_res);
+        let method_ref = ImplItemRef {
+            id: ImplItemId { owner_id: owner_id(2) },
+            ident: Ident::from_str("foo"),
+            kind: AssocItemKind::Fn { has_self: false },
+            span: DUMMY_SP,
+            trait_item_def_id: None,
+        };
+        let impl_items = [method_ref];
+        let impl_ = Impl {
+            unsafety: Unsafety::Normal,
+            polarity: ImplPolarity::Positive,
+            defaultness: Defaultness::Final,
+            defaultness_span: None,
+            constness: Constness::NotConst,
+            generics: Generics::empty(),
+            of_trait: None,
+            self_ty: &foo_ty,
+            items: &impl_items,
+        };
+        let impl_item = Item {
+            ident: Ident::empty(),
+            owner_id: owner_id(1),
+            kind: ItemKind::Impl(&impl_),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let decl = fn_decl(&[]);
+        let sig = fn_sig(&decl);
+        let method = ImplItem {
+            ident: Ident::from_str("foo"),
+            owner_id: owner_id(2),
+            generics: Generics::empty(),
+            kind: ImplItemKind::Fn(sig, BodyId { hir_id: HirId::INVALID }),
+            defaultness: Defaultness::Final,
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let impl_info = owner_info(Some(Node::Item(&impl_item)), &[]);
+        let method_info = owner_info(Some(Node::ImplItem(&method)), &[]);
+        let krate = krate(&[&impl_info, &method_info]);
+
+        let found = krate.find_inherent_method(foo_def_id, Symbol::intern("foo")).unwrap();
+        assert_eq!(found.owner_id, owner_id(2));
+        assert!(krate.find_inherent_method(foo_def_id, Symbol::intern("bar")).is_none());
+        let other_def_id = owner_id(8).to_def_id();
+        assert!(krate.find_inherent_method(other_def_id, Symbol::intern("foo")).is_none());
+    })
+}
+
+#[test]
+fn crate_find_field() {
+    create_default_session_globals_then(|| {
+        // `struct Foo { x: u8 }`
+        let never = ty(TyKind::Never);
+        let fields = [FieldDef {
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+            ident: Ident::from_str("x"),
+            hir_id: local_hir_id(1),
+            def_id: owner_id(2).def_id,
+            ty: &never,
+        }];
+        let foo = Item {
+            ident: Ident::from_str("Foo"),
+            owner_id: owner_id(1),
+            kind: ItemKind::Struct(VariantData::Struct(&fields, false), Generics::empty()),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let foo_info = owner_info(Some(Node::Item(&foo)), &[]);
+        let krate = krate(&[&foo_info]);
+
+        let foo_def_id = owner_id(1).to_def_id();
+        let x = krate.find_field(foo_def_id, Symbol::intern("x")).unwrap();
+        assert_eq!(x.hir_id, local_hir_id(1));
+        assert!(krate.find_field(foo_def_id, Symbol::intern("y")).is_none());
+        assert!(krate.find_field(owner_id(3).to_def_id(), Symbol::intern("x")).is_none());
+    })
+}
+
+#[test]
+fn ty_full_span() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `dyn Foo + 'static`, which lowering spans as a whole.
+        let foo_res = Res::Def(DefKind::Trait, owner_id(1).to_def_id());
+        let foo_path = simple_path(&arena, "Foo", foo_res);
+        let bounds = [poly_trait_ref(foo_path)];
+        let ident = Ident::new(kw::StaticLifetime, span(10, 17));
+        let static_lt = Lifetime { hir_id: HirId::INVALID, ident, res: LifetimeName::Static };
+        let dyn_ty = Ty {
+            span: span(0, 17),
+            ..ty(TyKind::TraitObject(&bounds, &static_lt, TraitObjectSyntax::Dyn))
+        };
+        assert_eq!(dyn_ty.full_span(), span(0, 17));
+
+        // `dyn Foo + '_`
+        let ident = Ident::new(kw::UnderscoreLifetime, span(10, 12));
+        let anon_lt = Lifetime { hir_id: HirId::INVALID, ident, res: LifetimeName::Infer };
+        let dyn_ty = Ty {
+            span: span(0, 12),
+            ..ty(TyKind::TraitObject(&bounds, &anon_lt, TraitObjectSyntax::Dyn))
+        };
+        assert_eq!(dyn_ty.full_span(), span(0, 12));
+        assert_eq!(ty(TyKind::Never).full_span(), DUMMY_SP);
+    })
+}
+
+#[test]
+fn bin_op_kind_swap() {
+    let comparisons = [
+        (BinOpKind::Eq, BinOpKind::Eq),
+        (BinOpKind::Ne, BinOpKind::Ne),
+        (BinOpKind::Lt, BinOpKind::Gt),
+        (BinOpKind::Le, BinOpKind::Ge),
+    ];
+    for (op, swapped) in comparisons {
+        assert_eq!(op.swap(), Some(swapped));
+        assert_eq!(swapped.swap(), Some(op));
+    }
+    assert_eq!(BinOpKind::Sub.swap(), None);
+    assert_eq!(BinOpKind::Div.swap(), None);
+    assert_eq!(BinOpKind::Shl.swap(), None);
+}
+
+#[test]
+fn expr_peel_derefs() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let x = path_expr(&arena, 2, "x", Res::Local(local_hir_id(1)));
+        assert_eq!(x.deref_count(), 0);
+        assert_eq!(x.peel_derefs().hir_id, x.hir_id);
+
+        // `**x`
+        let deref_x = expr(3, ExprKind::Unary(UnOp::Deref, &x));
+        let deref_deref_x = expr(4, ExprKind::Unary(UnOp::Deref, &deref_x));
+        assert_eq!(deref_deref_x.deref_count(), 2);
+        assert_eq!(deref_deref_x.peel_derefs().hir_id, x.hir_id);
+
+        // `!*x` stops at the negation.
+        let not_deref_x = expr(5, ExprKind::Unary(UnOp::Not, &deref_x));
+        assert_eq!(not_deref_x.deref_count(), 0);
+    })
+}
+
+#[test]
+fn expr_is_nested_comparison() {
+    let (a, b, c) = (unit_expr(1), unit_expr(2), unit_expr(3));
+    let lt = Spanned { node: BinOpKind::Lt, span: DUMMY_SP };
+
+    // `a < b`
+    let a_lt_b = expr(4, ExprKind::Binary(lt, &a, &b));
+    assert!(a_lt_b.is_nested_comparison().is_none());
+
+    // `a < b < c`
+    let chain = expr(5, ExprKind::Binary(lt, &a_lt_b, &c));
+    let (lhs, rhs) = chain.is_nested_comparison().unwrap();
+    assert_eq!((lhs.hir_id, rhs.hir_id), (a_lt_b.hir_id, c.hir_id));
+
+    // `(a < b) + c`
+    let add = Spanned { node: BinOpKind::Add, span: DUMMY_SP };
+    assert!(expr(6, ExprKind::Binary(add, &a_lt_b, &c)).is_nested_comparison().is_none());
+}
+
+fn module<'hir>(item_ids: &'hir [ItemId]) -> Mod<'hir> {
+    Mod { spans: ModSpans { inner_span: DUMMY_SP, inject_use_span: DUMMY_SP }, item_ids }
+}
+
+#[test]
+fn crate_module_tree() {
+    create_default_session_globals_then(|| {
+        // `mod a { extern crate c; } extern crate b;`
+        let (a_id, b_id, c_id) = (owner_id(1), owner_id(2), owner_id(3));
+        let a_items = [ItemId { owner_id: c_id }];
+        let a_mod = module(&a_items);
+        let item = |owner_id, name, kind| Item {
+            ident: Ident::from_str(name),
+            owner_id,
+            kind,
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let a = item(a_id, "a", ItemKind::Mod(&a_mod));
+        let b = item(b_id, "b", ItemKind::ExternCrate(None));
+        let c = item(c_id, "c", ItemKind::ExternCrate(None));
+        let root_items = [ItemId { owner_id: a_id }, ItemId { owner_id: b_id }];
+        let root_mod = module(&root_items);
+        let infos = [
+            owner_info(Some(Node::Crate(&root_mod)), &[]),
+            owner_info(Some(Node::Item(&a)), &[]),
+            owner_info(Some(Node::Item(&b)), &[]),
+            owner_info(Some(Node::Item(&c)), &[]),
+        ];
+        let owners = infos.iter().map(MaybeOwner::Owner).collect();
+        let krate = Crate { owners, opt_hir_hash: None };
+
+        let tree = krate.module_tree();
+        assert_eq!(tree.hir_id, CRATE_HIR_ID);
+        assert_eq!(tree.item_ids, root_items);
+        assert_eq!(tree.children.len(), 1);
+        let child = &tree.children[0];
+        assert_eq!(child.hir_id, a_id.into());
+        assert_eq!(child.item_ids, a_items);
+        assert!(child.children.is_empty());
+    })
+}
+
+#[test]
+fn crate_closures() {
+    create_default_session_globals_then(|| {
+        // `(|| (), move || ())`
+        let decl = fn_decl(&[]);
+        let closure = |capture_clause, body| Closure {
+            def_id: CRATE_DEF_ID,
+            binder: ClosureBinder::Default,
+            constness: Constness::NotConst,
+            capture_clause,
+            bound_generic_params: &[],
+            fn_decl: &decl,
+            body: BodyId { hir_id: local_hir_id(body) },
+            fn_decl_span: DUMMY_SP,
+            fn_arg_span: None,
+            movability: None,
+        };
+        let (by_ref, by_value) = (closure(CaptureBy::Ref, 2), closure(CaptureBy::Value, 4));
+        let exprs = [
+            expr(1, ExprKind::Closure(&by_ref)),
+            expr(3, ExprKind::Closure(&by_value)),
+        ];
+        let tuple = expr(5, ExprKind::Tup(&exprs));
+        let mut info = owner_info(None, &[]);
+        info.nodes.nodes = [&exprs[0], &exprs[1], &tuple]
+            .into_iter()
+            .map(|expr| Some(ParentedNode { parent: ItemLocalId::INVALID, node: Node::Expr(expr) }))
+            .collect();
+        let krate = krate(&[&info]);
+
+        let closures: Vec<_> = krate
+            .closures()
+            .map(|(expr, capture_clause, body)| (expr.hir_id, capture_clause, body.hir_id))
+            .collect();
+        assert_eq!(
+            closures,
+            [
+                (exprs[0].hir_id, CaptureBy::Ref, local_hir_id(2)),
+                (exprs[1].hir_id, CaptureBy::Value, local_hir_id(4)),
+            ]
+        );
+    })
+}
+
+#[test]
+fn crate_root_main_fn() {
+    create_default_session_globals_then(|| {
+        let decl = fn_decl(&[]);
+        let sig = fn_sig(&decl);
+        let body = BodyId { hir_id: HirId::INVALID };
+        let item = |owner_id, name, kind| Item {
+            ident: Ident::from_str(name),
+            owner_id,
+            kind,
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let main = item(owner_id(1), "main", ItemKind::Fn(sig, Generics::empty(), body));
+        let nested_items = [ItemId { owner_id: owner_id(1) }];
+        let nested_mod = module(&nested_items);
+        let a = item(owner_id(2), "a", ItemKind::Mod(&nested_mod));
+        let main_info = owner_info(Some(Node::Item(&main)), &[]);
+        let a_info = owner_info(Some(Node::Item(&a)), &[]);
+
+        // `fn main() {}`
+        let root_items = [ItemId { owner_id: owner_id(1) }];
+        let root_mod = module(&root_items);
+        let root_info = owner_info(Some(Node::Crate(&root_mod)), &[]);
+        let owners = [&root_info, &main_info, &a_info].into_iter().map(MaybeOwner::Owner);
+        let krate = Crate { owners: owners.collect(), opt_hir_hash: None };
+        assert_eq!(krate.root_main_fn().map(|item| item.owner_id), Some(owner_id(1)));
+
+        // `mod a { fn main() {} }`
+        let root_items = [ItemId { owner_id: owner_id(2) }];
+        let root_mod = module(&root_items);
+        let root_info = owner_info(Some(Node::Crate(&root_mod)), &[]);
+        let owners = [&root_info, &main_info, &a_info].into_iter().map(MaybeOwner::Owner);
+        let krate = Crate { owners: owners.collect(), opt_hir_hash: None };
+        assert!(krate.root_main_fn().is_none());
+    })
+}
+
+#[test]
+fn pat_count_bindings() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let binding = |name, canonical_id| {
+            let ident = Ident::from_str(name);
+            pat(PatKind::Binding(BindingAnnotation::NONE, canonical_id, ident, None))
+        };
+
+        // `(a, b, c)`
+        let fields = [
+            binding("a", local_hir_id(1)),
+            binding("b", local_hir_id(2)),
+            binding("c", local_hir_id(3)),
+        ];
+        assert_eq!(pat(PatKind::Tuple(&fields, DotDotPos::new(None))).count_bindings(), 3);
+
+        // `A(x) | B(x)`, where both `x` share the canonical `HirId` of the first one.
+        let x_id = local_hir_id(4);
+        let (a_x, b_x) = ([binding("x", x_id)], [binding("x", x_id)]);
+        let a_path = simple_path(&arena, "A", Res::Err);
+        let b_path = simple_path(&arena, "B", Res::Err);
+        let alternatives = [
+            pat(PatKind::TupleStruct(QPath::Resolved(None, a_path), &a_x, DotDotPos::new(None))),
+            pat(PatKind::TupleStruct(QPath::Resolved(None, b_path), &b_x, DotDotPos::new(None))),
+        ];
+        assert_eq!(pat(PatKind::Or(&alternatives)).count_bindings(), 1);
+        assert_eq!(pat(PatKind::Wild).count_bindings(), 0);
+    })
+}
+
+fn loop_expr<'hir>(local_id: u32, body: &'hir Block<'hir>, source: LoopSource) -> Expr<'hir> {
+    expr(local_id, ExprKind::Loop(body, None, source, DUMMY_SP))
+}
+
+#[test]
+fn loop_source_predicates() {
+    let classify = |source: LoopSource| (source.is_loop(), source.is_while(), source.is_for());
+    assert_eq!(classify(LoopSource::Loop), (true, false, false));
+    assert_eq!(classify(LoopSource::While), (false, true, false));
+    assert_eq!(classify(LoopSource::ForLoop), (false, false, true));
+}
+
+#[test]
+fn expr_kind_loop_label() {
+    create_default_session_globals_then(|| {
+        let label = Label { ident: Ident::from_str("'a") };
+        let body = block(&[], None);
+        for source in [LoopSource::Loop, LoopSource::While, LoopSource::ForLoop] {
+            // `'a: loop {}`, `'a: while .. {}` and `'a: for .. {}`
+            let labeled = ExprKind::Loop(&body, Some(label), source, DUMMY_SP);
+            assert_eq!(labeled.loop_label(), Some(label));
+            assert!(loop_expr(1, &body, source).kind.loop_label().is_none());
+        }
+
+        // `'a: {}`
+        assert_eq!(ExprKind::Block(&body, Some(label)).loop_label(), Some(label));
+        assert!(ExprKind::Block(&body, None).loop_label().is_none());
+        assert!(unit_expr(2).kind.loop_label().is_none());
+    })
+}
+
+#[test]
+fn expr_is_infinite_loop() {
+    // `loop {}`
+    let empty = block(&[], None);
+    assert!(loop_expr(1, &empty, LoopSource::Loop).is_infinite_loop());
+
+    // `loop { break; }`
+    let to_outer = Destination { label: None, target_id: Ok(local_hir_id(1)) };
+    let break_outer = expr(2, ExprKind::Break(to_outer, None));
+    let break_outer = [semi(&break_outer)];
+    let breaking = block(&break_outer, None);
+    assert!(!loop_expr(1, &breaking, LoopSource::Loop).is_infinite_loop());
+
+    // `loop { for _ in x { break; } }`, with the inner `break` targeting the `for` loop.
+    let to_inner = Destination { label: None, target_id: Ok(local_hir_id(3)) };
+    let break_inner = expr(4, ExprKind::Break(to_inner, None));
+    let break_inner = [semi(&break_inner)];
+    let inner_body = block(&break_inner, None);
+    let inner_loop = loop_expr(3, &inner_body, LoopSource::ForLoop);
+    let stmts = [semi(&inner_loop)];
+    let nested = block(&stmts, None);
+    assert!(loop_expr(1, &nested, LoopSource::Loop).is_infinite_loop());
+    assert!(!inner_loop.is_infinite_loop());
+}
+
+#[test]
+fn fn_sig_decl_span() {
+    create_default_session_globals_then(|| {
+        // `fn f(a: u8, b: u8) -> u8`
+        let spanned_ty = |lo, hi| Ty { span: span(lo, hi), ..ty(TyKind::Never) };
+        let inputs = [spanned_ty(8, 10), spanned_ty(15, 17)];
+        let output = spanned_ty(22, 24);
+        let mut decl = FnDecl { output: FnRetTy::Return(&output), ..fn_decl(&inputs) };
+        let sig = FnSig { span: span(0, 24), ..fn_sig(&decl) };
+        assert_eq!(sig.decl_span(), span(8, 24));
+
+        // `fn f()`
+        decl.inputs = &[];
+        decl.output = FnRetTy::DefaultReturn(span(6, 6));
+        let sig = FnSig { span: span(0, 6), ..fn_sig(&decl) };
+        assert_eq!(sig.decl_span(), span(6, 6));
+    })
+}
+
+#[test]
+fn ty_as_ref() {
+    create_default_session_globals_then(|| {
+        let t = ty(TyKind::Never);
+
+        // `&'a mut T`
+        let a = lifetime("'a", LifetimeName::Param(CRATE_DEF_ID));
+        let mut_ref = ty(TyKind::Ref(&a, MutTy { ty: &t, mutbl: Mutability::Mut }));
+        let (lt, referent, mutbl) = mut_ref.as_ref().unwrap();
+        assert_eq!(lt.ident.as_str(), "'a");
+        assert!(matches!(referent.kind, TyKind::Never));
+        assert_eq!(mutbl, Mutability::Mut);
+
+        // `&T`
+        let elided = lifetime("", LifetimeName::Infer);
+        let shared_ref = ty(TyKind::Ref(&elided, MutTy { ty: &t, mutbl: Mutability::Not }));
+        let (lt, _, mutbl) = shared_ref.as_ref().unwrap();
+        assert!(lt.is_elided());
+        assert_eq!(mutbl, Mutability::Not);
+        assert!(t.as_ref().is_none());
+    })
+}
+
+#[test]
+fn expr_is_pure_block() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `{ x }`
+        let x = path_expr(&arena, 2, "x", Res::Local(local_hir_id(1)));
+        let x_block = block(&[], Some(&x));
+        let x_block = expr(3, ExprKind::Block(&x_block, None));
+        assert_eq!(x_block.is_pure_block().map(|tail| tail.hir_id), Some(x.hir_id));
+
+        // `{ let y = 1; y }`
+        let y_id = local_hir_id(4);
+        let y_ident = Ident::from_str("y");
+        let y_pat = pat(PatKind::Binding(BindingAnnotation::NONE, y_id, y_ident, None));
+        let one = int_lit(1);
+        let one = expr(5, ExprKind::Lit(&one));
+        let y_local = local(&y_pat, Some(&one), LocalSource::Normal);
+        let stmts =
+            [Stmt { hir_id: HirId::INVALID, kind: StmtKind::Local(&y_local), span: DUMMY_SP }];
+        let y = path_expr(&arena, 6, "y", Res::Local(y_id));
+        let y_block = block(&stmts, Some(&y));
+        assert!(expr(7, ExprKind::Block(&y_block, None)).is_pure_block().is_none());
+        assert!(x.is_pure_block().is_none());
+    })
+}
+
+#[test]
+fn expr_as_if() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let c = path_expr(&arena, 4, "c", Res::Local(local_hir_id(1)));
+        let a = path_expr(&arena, 5, "a", Res::Local(local_hir_id(2)));
+        let b = path_expr(&arena, 6, "b", Res::Local(local_hir_id(3)));
+        let (a_block, b_block) = (block(&[], Some(&a)), block(&[], Some(&b)));
+        let a_block = expr(7, ExprKind::Block(&a_block, None));
+        let b_block = expr(8, ExprKind::Block(&b_block, None));
+
+        // `if c { a } else { b }`
+        let if_else = expr(9, ExprKind::If(&c, &a_block, Some(&b_block)));
+        let (cond, then, els) = if_else.as_if().unwrap();
+        assert_eq!(cond.hir_id, c.hir_id);
+        assert_eq!(then.hir_id, a_block.hir_id);
+        assert_eq!(els.map(|els| els.hir_id), Some(b_block.hir_id));
+
+        // `if c { a }`
+        let if_ = expr(10, ExprKind::If(&c, &a_block, None));
+        let (cond, then, els) = if_.as_if().unwrap();
+        assert_eq!((cond.hir_id, then.hir_id), (c.hir_id, a_block.hir_id));
+        assert!(els.is_none());
+        assert!(a_block.as_if().is_none());
+    })
+}
+
+/// `extern crate name;`, as the owner `index`.
+fn extern_crate<'hir>(index: u32, name: &str) -> Item<'hir> {
+    Item {
+        ident: Ident::from_str(name),
+        owner_id: owner_id(index),
+        kind: ItemKind::ExternCrate(None),
+        span: DUMMY_SP,
+        vis_span: DUMMY_SP,
+    }
+}
+
+#[test]
+fn crate_items_with_attr() {
+    use rustc_ast::attr::{mk_attr_word, AttrIdGenerator};
+
+    create_default_session_globals_then(|| {
+        // `#[inline] extern crate a; extern crate b;`
+        let (a, b) = (extern_crate(1, "a"), extern_crate(2, "b"));
+        let inline =
+            [mk_attr_word(&AttrIdGenerator::new(), ast::AttrStyle::Outer, sym::inline, DUMMY_SP)];
+        let a_attrs = std::iter::once((ItemLocalId::new(0), &inline[..])).collect();
+        let a_info = OwnerInfo {
+            attrs: AttributeMap { map: a_attrs, opt_hash: None },
+            ..owner_info(Some(Node::Item(&a)), &[])
+        };
+        let b_info = owner_info(Some(Node::Item(&b)), &[]);
+        let krate = krate(&[&a_info, &b_info]);
+
+        let inline_items: Vec<_> =
+            krate.items_with_attr(sym::inline).map(|item| item.ident).collect();
+        assert_eq!(inline_items, [a.ident]);
+        assert_eq!(krate.items_with_attr(sym::cold).count(), 0);
+    })
+}
+
+#[test]
+fn generic_arg_kind_matches_param() {
+    create_default_session_globals_then(|| {
+        let never = ty(TyKind::Never);
+        let a = lifetime("'a", LifetimeName::Param(CRATE_DEF_ID));
+        let lifetime_arg = GenericArg::Lifetime(&a);
+        let type_arg = GenericArg::Type(&never);
+        let infer_arg = GenericArg::Infer(InferArg { hir_id: HirId::INVALID, span: DUMMY_SP });
+
+        let lifetime_param = GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit };
+        let type_param = GenericParamKind::Type { default: None, synthetic: false };
+        let const_param = GenericParamKind::Const { ty: &never, default: None };
+
+        assert!(!kind_matches_param(&type_arg, &lifetime_param));
+        assert!(kind_matches_param(&type_arg, &type_param));
+        assert!(kind_matches_param(&lifetime_arg, &lifetime_param));
+        assert!(!kind_matches_param(&lifetime_arg, &const_param));
+        assert!(kind_matches_param(&infer_arg, &const_param));
+        assert!(!kind_matches_param(&infer_arg, &lifetime_param));
+    })
+}
+
+#[test]
+fn generic_arg_accessors() {
+    create_default_session_globals_then(|| {
+        let never = ty(TyKind::Never);
+        let a = lifetime("'a", LifetimeName::Param(CRATE_DEF_ID));
+        let lifetime_arg = GenericArg::Lifetime(&a);
+        let type_arg = GenericArg::Type(&never);
+
+        assert!(lifetime_arg.is_lifetime() && !lifetime_arg.is_type() && !lifetime_arg.is_const());
+        assert_eq!(lifetime_arg.as_lifetime().map(|lt| lt.ident), Some(a.ident));
+        assert!(lifetime_arg.as_type().is_none());
+        assert!(lifetime_arg.as_const().is_none());
+
+        assert!(type_arg.is_type() && !type_arg.is_lifetime());
+        assert!(matches!(type_arg.as_type(), Some(Ty { kind: TyKind::Never, .. })));
+        assert!(type_arg.as_lifetime().is_none());
+    })
+}
+
+#[test]
+fn ty_path_segments() {
+    create_default_session_globals_then(|| {
+        // `std::vec::Vec`
+        let vec_res = Res::Def(DefKind::Struct, owner_id(1).to_def_id());
+        let std_res = Res::Def(DefKind::Mod, owner_id(2).to_def_id());
+        let vec_mod_res = Res::Def(DefKind::Mod, owner_id(3).to_def_id());
+        let segments =
+            [segment("std", std_res), segment("vec", vec_mod_res), segment("Vec", vec_res)];
+        let vec_path = path(vec_res, &segments);
+        let vec_ty = ty(TyKind::Path(QPath::Resolved(None, &vec_path)));
+        let names: Vec<_> =
+            vec_ty.path_segments().unwrap().iter().map(|segment| segment.ident.as_str()).collect();
+        assert_eq!(names, ["std", "vec", "Vec"]);
+
+        assert!(ty(TyKind::Never).path_segments().is_none());
+    })
+}
+
+#[test]
+fn block_value_expr() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `{ x }`
+        let x = path_expr(&arena, 2, "x", Res::Local(local_hir_id(1)));
+        let tail = block(&[], Some(&x));
+        assert_eq!(tail.value_expr().map(|expr| expr.hir_id), Some(x.hir_id));
+
+        // `{ foo(); }`
+        let foo = path_expr(&arena, 3, "foo", Res::Err);
+        let call = expr(4, ExprKind::Call(&foo, &[]));
+        let stmts = [semi(&call)];
+        let trailing_semi = block(&stmts, None);
+        assert_eq!(trailing_semi.value_expr().map(|expr| expr.hir_id), Some(call.hir_id));
+
+        assert!(block(&[], None).value_expr().is_none());
+    })
+}
+
+#[test]
+fn block_tail_expr_and_innermost_block() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `{ { x } }`
+        let x = path_expr(&arena, 2, "x", Res::Local(local_hir_id(1)));
+        let inner = block(&[], Some(&x));
+        let inner_expr = expr(3, ExprKind::Block(&inner, None));
+        let outer = block(&[], Some(&inner_expr));
+        assert_eq!(outer.tail_expr().map(|expr| expr.hir_id), Some(inner_expr.hir_id));
+        assert_eq!(outer.innermost_block().expr.map(|expr| expr.hir_id), Some(x.hir_id));
+        assert_eq!(inner.innermost_block().expr.map(|expr| expr.hir_id), Some(x.hir_id));
+
+        // `{ x; }`
+        let stmts = [semi(&x)];
+        let no_tail = block(&stmts, None);
+        assert!(no_tail.tail_expr().is_none());
+        assert_eq!(no_tail.innermost_block().stmts.len(), 1);
+    })
+}
+
+#[test]
+fn match_source_is_desugared() {
+    use MatchSource::*;
+    let classify = |source: MatchSource| (source.is_desugared(), source.is_loop_desugar());
+    assert_eq!(classify(Normal), (false, false));
+    assert_eq!(classify(ForLoopDesugar), (true, true));
+    assert_eq!(classify(TryDesugar), (true, false));
+    assert_eq!(classify(AwaitDesugar), (true, false));
+    assert_eq!(classify(FormatArgs), (true, false));
+}
+
+#[test]
+fn expr_method_chain() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `x.a().b()`
+        let x = path_expr(&arena, 2, "x", Res::Local(local_hir_id(1)));
+        let a = segment("a", Res::Err);
+        let x_a = expr(3, ExprKind::MethodCall(&a, &x, &[], DUMMY_SP));
+        let b = segment("b", Res::Err);
+        let x_a_b = expr(4, ExprKind::MethodCall(&b, &x_a, &[], DUMMY_SP));
+
+        let chain = x_a_b.method_chain();
+        let names: Vec<_> = chain.iter().map(|(segment, _)| segment.ident.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(chain.iter().all(|(_, args)| args.is_empty()));
+        assert!(x.method_chain().is_empty());
+    })
+}
+
+fn generic_param<'hir>(name: &str, kind: GenericParamKind<'hir>) -> GenericParam<'hir> {
+    GenericParam {
+        hir_id: HirId::INVALID,
+        def_id: CRATE_DEF_ID,
+        name: ParamName::Plain(Ident::from_str(name)),
+        span: DUMMY_SP,
+        pure_wrt_drop: false,
+        kind,
+        colon_span: None,
+        source: GenericParamSource::Generics,
+    }
+}
+
+#[test]
+fn crate_generic_param_counts() {
+    create_default_session_globals_then(|| {
+        // `struct S<'a, T, U, const N: usize>;`
+        let n_ty = ty(TyKind::Never);
+        let params = [
+            generic_param("'a", GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit }),
+            generic_param("T", GenericParamKind::Type { default: None, synthetic: false }),
+            generic_param("U", GenericParamKind::Type { default: None, synthetic: false }),
+            generic_param("N", GenericParamKind::Const { ty: &n_ty, default: None }),
+        ];
+        let generics = Generics { params: &params, ..*Generics::empty() };
+        let s = Item {
+            ident: Ident::from_str("S"),
+            owner_id: owner_id(1),
+            kind: ItemKind::Struct(VariantData::Unit(HirId::INVALID, CRATE_DEF_ID), &generics),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        // `extern crate c;`
+        let c = extern_crate(2, "c");
+        let s_info = owner_info(Some(Node::Item(&s)), &[]);
+        let c_info = owner_info(Some(Node::Item(&c)), &[]);
+        let krate = krate(&[&s_info, &c_info]);
+
+        let counts = krate.generic_param_counts();
+        assert_eq!(counts.len(), 2);
+        let s_counts = &counts[&s.hir_id()];
+        assert_eq!((s_counts.lifetimes, s_counts.types, s_counts.consts), (1, 2, 1));
+        let c_counts = &counts[&c.hir_id()];
+        assert_eq!((c_counts.lifetimes, c_counts.types, c_counts.consts), (0, 0, 0));
+    })
+}
+
+#[test]
+fn generics_params_by_kind() {
+    create_default_session_globals_then(|| {
+        // `<'a, T, const N: usize>`
+        let n_ty = ty(TyKind::Never);
+        let params = [
+            generic_param("'a", GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit }),
+            generic_param("T", GenericParamKind::Type { default: None, synthetic: false }),
+            generic_param("N", GenericParamKind::Const { ty: &n_ty, default: None }),
+        ];
+        let generics = Generics { params: &params, ..*Generics::empty() };
+
+        let name = |param: &GenericParam<'_>| param.name.ident().name;
+        assert_eq!(generics.lifetimes().map(name).collect::<Vec<_>>(), [Symbol::intern("'a")]);
+        assert_eq!(generics.types().map(name).collect::<Vec<_>>(), [Symbol::intern("T")]);
+        assert_eq!(generics.consts().map(name).collect::<Vec<_>>(), [Symbol::intern("N")]);
+        assert_eq!(Generics::empty().types().count(), 0);
+    })
+}
+
+#[test]
+fn generics_get_named_by_kind() {
+    create_default_session_globals_then(|| {
+        // `<N, const N: usize>`, as recovered from erroneous code
+        let n_ty = ty(TyKind::Never);
+        let params = [
+            GenericParam {
+                hir_id: local_hir_id(1),
+                ..generic_param("N", GenericParamKind::Type { default: None, synthetic: false })
+            },
+            GenericParam {
+                hir_id: local_hir_id(2),
+                ..generic_param("N", GenericParamKind::Const { ty: &n_ty, default: None })
+            },
+        ];
+        let generics = Generics { params: &params, ..*Generics::empty() };
+        let n = Symbol::intern("N");
+
+        assert_eq!(generics.get_named(n).map(|param| param.hir_id), Some(local_hir_id(1)));
+        assert_eq!(generics.get_named_type(n).map(|param| param.hir_id), Some(local_hir_id(1)));
+        assert_eq!(generics.get_named_const(n).map(|param| param.hir_id), Some(local_hir_id(2)));
+        assert!(generics.get_named_lifetime(n).is_none());
+    })
+}
+
+#[test]
+fn generics_lifetime_bounds() {
+    create_default_session_globals_then(|| {
+        // `fn f<'a, 'b>() where 'a: 'b`
+        let lifetime_param = GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit };
+        let params = [generic_param("'a", lifetime_param), generic_param("'b", lifetime_param)];
+        let a = lifetime("'a", LifetimeName::Param(owner_id(1).def_id));
+        let b = lifetime("'b", LifetimeName::Param(owner_id(2).def_id));
+        let bounds = [GenericBound::Outlives(&b)];
+        let predicates = [WherePredicate::RegionPredicate(WhereRegionPredicate {
+            span: DUMMY_SP,
+            in_where_clause: true,
+            lifetime: &a,
+            bounds: &bounds,
+        })];
+        let generics = Generics {
+            params: &params,
+            predicates: &predicates,
+            has_where_clause_predicates: true,
+            ..*Generics::empty()
+        };
+
+        let a_bounds = generics.lifetime_bounds(Symbol::intern("'a"));
+        assert_eq!(a_bounds.iter().map(|lt| lt.ident).collect::<Vec<_>>(), [b.ident]);
+        assert!(generics.lifetime_bounds(Symbol::intern("'b")).is_empty());
+    })
+}
+
+#[test]
+fn generic_param_has_default() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `T = u8`
+        let u8_res = Res::PrimTy(PrimTy::Uint(UintTy::U8));
+        let u8_ty = path_ty(&arena, "u8", u8_res);
+        let kind = GenericParamKind::Type { default: Some(&u8_ty), synthetic: false };
+        assert!(generic_param("T", kind).has_default());
+
+        // `T`
+        let kind = GenericParamKind::Type { default: None, synthetic: false };
+        assert!(!generic_param("T", kind).has_default());
+        let kind = GenericParamKind::Lifetime { kind: LifetimeParamKind::Explicit };
+        assert!(!generic_param("'a", kind).has_default());
+    })
+}
+
+#[test]
+fn pat_as_struct() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `Foo { x, .. }`
+        let foo_res = Res::Def(DefKind::Struct, owner_id(1).to_def_id());
+        let foo_path = simple_path(&arena, "Foo", foo_res);
+        let x = Ident::from_str("x");
+        let x_pat = pat(PatKind::Binding(BindingAnnotation::NONE, local_hir_id(1), x, None));
+        let fields = [PatField {
+            hir_id: HirId::INVALID,
+            ident: x,
+            pat: &x_pat,
+            is_shorthand: true,
+            span: DUMMY_SP,
+        }];
+        let foo_pat = pat(PatKind::Struct(QPath::Resolved(None, foo_path), &fields, true));
+
+        let (qpath, fields, has_rest) = foo_pat.as_struct().unwrap();
+        assert!(matches!(qpath, QPath::Resolved(None, path) if path.res == foo_res));
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].ident, x);
+        assert!(fields[0].is_shorthand);
+        assert!(has_rest);
+        assert!(x_pat.as_struct().is_none());
+    })
+}
+
+#[test]
+fn pat_each_binding() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `Foo { x, y: ref y }`
+        let foo_res = Res::Def(DefKind::Struct, owner_id(1).to_def_id());
+        let foo_path = simple_path(&arena, "Foo", foo_res);
+        let (x, y) = (Ident::from_str("x"), Ident::from_str("y"));
+        let x_pat = Pat {
+            hir_id: local_hir_id(1),
+            ..pat(PatKind::Binding(BindingAnnotation::NONE, local_hir_id(1), x, None))
+        };
+        let y_pat = Pat {
+            hir_id: local_hir_id(2),
+            ..pat(PatKind::Binding(BindingAnnotation::REF, local_hir_id(2), y, None))
+        };
+        let field = |ident, pat, is_shorthand| PatField {
+            hir_id: HirId::INVALID,
+            ident,
+            pat,
+            is_shorthand,
+            span: DUMMY_SP,
+        };
+        let fields = [field(x, &x_pat, true), field(y, &y_pat, false)];
+        let foo_pat = pat(PatKind::Struct(QPath::Resolved(None, foo_path), &fields, false));
+
+        let mut bindings = vec![];
+        foo_pat.each_binding(|annotation, hir_id, _, ident| {
+            bindings.push((annotation, hir_id, ident))
+        });
+        assert_eq!(
+            bindings,
+            [
+                (BindingAnnotation::NONE, local_hir_id(1), x),
+                (BindingAnnotation::REF, local_hir_id(2), y),
+            ]
+        );
+    })
+}
+
+#[test]
+fn assoc_item_kind_descr() {
+    let never = ty(TyKind::Never);
+    let decl = fn_decl(&[]);
+    let sig = fn_sig(&decl);
+    let body = BodyId { hir_id: HirId::INVALID };
+
+    assert_eq!(TraitItemKind::Const(&never, None).descr(), "associated constant");
+    assert_eq!(TraitItemKind::Fn(sig, TraitFn::Provided(body)).descr(), "method");
+    assert_eq!(TraitItemKind::Type(&[], None).descr(), "associated type");
+
+    assert_eq!(ImplItemKind::Const(&never, body).descr(), "associated constant");
+    assert_eq!(ImplItemKind::Fn(sig, body).descr(), "method");
+    assert_eq!(ImplItemKind::Type(&never).descr(), "associated type");
+}
+
+#[test]
+fn crate_body_owner_kind() {
+    create_default_session_globals_then(|| {
+        let body_id =
+            |owner| BodyId { hir_id: HirId { owner, local_id: ItemLocalId::new(1) } };
+
+        // `fn f() {}`
+        let decl = fn_decl(&[]);
+        let sig = fn_sig(&decl);
+        let fn_body = body_id(owner_id(1));
+        let f = Item {
+            ident: Ident::from_str("f"),
+            owner_id: owner_id(1),
+            kind: ItemKind::Fn(sig, Generics::empty(), fn_body),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+
+        // `const C: ! = ..;`
+        let never = ty(TyKind::Never);
+        let const_body = body_id(owner_id(2));
+        let c = Item {
+            ident: Ident::from_str("C"),
+            owner_id: owner_id(2),
+            kind: ItemKind::Const(&never, const_body),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let f_info = owner_info(Some(Node::Item(&f)), &[]);
+        let c_info = owner_info(Some(Node::Item(&c)), &[]);
+        let krate = krate(&[&f_info, &c_info]);
+
+        assert!(matches!(krate.body_owner_kind(fn_body), Some(BodyOwnerKind::Fn)));
+        assert!(matches!(krate.body_owner_kind(const_body), Some(BodyOwnerKind::Const)));
+        assert!(krate.body_owner_kind(body_id(owner_id(3))).is_none());
+    })
+}
+
+#[test]
+fn expr_is_str_lit_add() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let add = Spanned { node: BinOpKind::Add, span: DUMMY_SP };
+
+        // `"a" + x`
+        let a = LitKind::Str(Symbol::intern("a"), ast::StrStyle::Cooked);
+        let a = Spanned { node: a, span: DUMMY_SP };
+        let a = expr(1, ExprKind::Lit(&a));
+        let x = path_expr(&arena, 3, "x", Res::Local(local_hir_id(2)));
+        assert!(expr(4, ExprKind::Binary(add, &a, &x)).is_str_lit_add());
+
+        // `1 + 2`
+        let (one, two) = (int_lit(1), int_lit(2));
+        let (one, two) = (expr(5, ExprKind::Lit(&one)), expr(6, ExprKind::Lit(&two)));
+        assert!(!expr(7, ExprKind::Binary(add, &one, &two)).is_str_lit_add());
+    })
+}
+
+#[test]
+fn ty_generic_args() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `Vec<u8>`
+        let u8_res = Res::PrimTy(PrimTy::Uint(UintTy::U8));
+        let u8_ty = path_ty(&arena, "u8", u8_res);
+        let args = [GenericArg::Type(&u8_ty)];
+        let generic_args = GenericArgs { args: &args, ..GenericArgs::none() };
+        let vec_res = Res::Def(DefKind::Struct, owner_id(1).to_def_id());
+        let vec_segments = [PathSegment { args: Some(&generic_args), ..segment("Vec", vec_res) }];
+        let vec_path = path(vec_res, &vec_segments);
+        let vec_ty = ty(TyKind::Path(QPath::Resolved(None, &vec_path)));
+
+        let vec_args = vec_ty.generic_args().unwrap();
+        let [GenericArg::Type(arg)] = vec_args.args else { panic!("expected one type argument") };
+        assert_eq!(arg.path_segments().map(|segments| segments[0].res), Some(u8_res));
+        assert!(u8_ty.generic_args().is_none());
+        assert!(ty(TyKind::Never).generic_args().is_none());
+    })
+}
+
+#[test]
+fn ty_kind_descr() {
+    create_default_session_globals_then(|| {
+        let never = ty(TyKind::Never);
+        assert_eq!(never.kind.descr(), "never type");
+        assert_eq!(TyKind::Slice(&never).descr(), "slice");
+        let mut_ty = MutTy { ty: &never, mutbl: Mutability::Not };
+        assert_eq!(TyKind::Ptr(mut_ty).descr(), "raw pointer");
+        let lt = lifetime("'static", LifetimeName::Static);
+        assert_eq!(TyKind::Ref(&lt, mut_ty).descr(), "reference");
+        assert_eq!(TyKind::Tup(&[]).descr(), "tuple");
+        assert_eq!(TyKind::Infer.descr(), "inferred type");
+    })
+}
+
+#[test]
+fn ty_walk() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `&'a (&'b u32, *const T)`
+        let u32_res = Res::PrimTy(PrimTy::Uint(UintTy::U32));
+        let u32_ty = path_ty(&arena, "u32", u32_res);
+        let t_res = Res::Def(DefKind::TyParam, owner_id(1).to_def_id());
+        let t_ty = path_ty(&arena, "T", t_res);
+        let (a, b) = (lifetime("'a", LifetimeName::Static), lifetime("'b", LifetimeName::Static));
+        let members = [
+            ty(TyKind::Ref(&b, MutTy { ty: &u32_ty, mutbl: Mutability::Not })),
+            ty(TyKind::Ptr(MutTy { ty: &t_ty, mutbl: Mutability::Not })),
+        ];
+        let tuple = ty(TyKind::Tup(&members));
+        let outer = ty(TyKind::Ref(&a, MutTy { ty: &tuple, mutbl: Mutability::Not }));
+
+        let mut refs = 0;
+        let mut visited = 0;
+        outer.walk(|ty| {
+            visited += 1;
+            refs += matches!(ty.kind, TyKind::Ref(..)) as usize;
+            true
+        });
+        assert_eq!((refs, visited), (2, 6));
+
+        let mut visited = 0;
+        outer.walk(|ty| {
+            visited += 1;
+            !matches!(ty.kind, TyKind::Tup(_))
+        });
+        assert_eq!(visited, 2);
+    })
+}
+
+#[test]
+fn ty_as_opaque_def() {
+    create_default_session_globals_then(|| {
+        // The return type of `fn f<'a>() -> impl Trait + 'a`, which refers to the opaque type
+        // definition and captures `'a`
+        let opaque_id = ItemId { owner_id: owner_id(2) };
+        let a = lifetime("'a", LifetimeName::Param(owner_id(3).def_id));
+        let args = [GenericArg::Lifetime(&a)];
+        let ret_ty = ty(TyKind::OpaqueDef(opaque_id, &args, false));
+
+        let (item_id, captured) = ret_ty.as_opaque_def().unwrap();
+        assert_eq!(item_id, opaque_id);
+        assert!(matches!(captured, [GenericArg::Lifetime(lt)] if lt.ident.name == a.ident.name));
+        assert!(ty(TyKind::Never).as_opaque_def().is_none());
+    })
+}
+
+fn impl_<'hir>(of_trait: Option<TraitRef<'hir>>, self_ty: &'hir Ty<'hir>) -> Impl<'hir> {
+    Impl {
+        unsafety: Unsafety::Normal,
+        polarity: ImplPolarity::Positive,
+        defaultness: Defaultness::Final,
+        defaultness_span: None,
+        constness: Constness::NotConst,
+        generics: Generics::empty(),
+        of_trait,
+        self_ty,
+        items: &[],
+    }
+}
+
+#[test]
+fn crate_impl_trait_refs() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        // `impl Clone for ! {}`, `impl Copy for ! {}` and `impl ! {}`
+        let clone_res = Res::Def(DefKind::Trait, owner_id(8).to_def_id());
+        let clone_path = simple_path(&arena, "Clone", clone_res);
+        let copy_res = Res::Def(DefKind::Trait, owner_id(9).to_def_id());
+        let copy_path = simple_path(&arena, "Copy", copy_res);
+        let never = ty(TyKind::Never);
+        let impls = [
+            impl_(Some(TraitRef { path: clone_path, hir_ref_id: HirId::INVALID }), &never),
+            impl_(Some(TraitRef { path: copy_path, hir_ref_id: HirId::INVALID }), &never),
+            impl_(None, &never),
+        ];
+        let item = |index, impl_| Item {
+            ident: Ident::empty(),
+            owner_id: owner_id(index),
+            kind: ItemKind::Impl(impl_),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let clone_impl = item(1, &impls[0]);
+        let copy_impl = item(2, &impls[1]);
+        let inherent_impl = item(3, &impls[2]);
+        let clone_info = owner_info(Some(Node::Item(&clone_impl)), &[]);
+        let copy_info = owner_info(Some(Node::Item(&copy_impl)), &[]);
+        let inherent_info = owner_info(Some(Node::Item(&inherent_impl)), &[]);
+        let krate = krate(&[&clone_info, &copy_info, &inherent_info]);
+
+        let trait_refs: Vec<_> = krate
+            .impl_trait_refs()
+            .map(|(item, trait_ref)| (item.owner_id, trait_ref.trait_def_id()))
+            .collect();
+        assert_eq!(
+            trait_refs,
+            [(owner_id(1), clone_res.opt_def_id()), (owner_id(2), copy_res.opt_def_id())]
+        );
+    })
+}
+
+#[test]
+fn crate_foreign_impls() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let foreign_def_id = |index| DefId { krate: CrateNum::from_u32(1), index };
+        let display_res = Res::Def(DefKind::Trait, foreign_def_id(DefIndex::from_u32(1)));
+        let display_path = simple_path(&arena, "Display", display_res);
+        let vec_res = Res::Def(DefKind::Struct, foreign_def_id(DefIndex::from_u32(2)));
+        let vec_ty = path_ty(&arena, "Vec", vec_res);
+        let foo_res = Res::Def(DefKind::Struct, owner_id(9).to_def_id());
+        let foo_ty = path_ty(&arena, "Foo", foo_res);
+        let u8_ty = path_ty(&arena, "u8", Res::PrimTy(PrimTy::Uint(UintTy::U8)));
+        let t_ty = path_ty(&arena, "T", Res::Def(DefKind::TyParam, owner_id(10).to_def_id()));
+        let elided = lifetime("'_", LifetimeName::Infer);
+        let foo_ref = ty(TyKind::Ref(&elided, MutTy { ty: &foo_ty, mutbl: Mutability::Not }));
+        let foo_slice = ty(TyKind::Slice(&foo_ty));
+
+        // `impl Display for Vec {}`, `impl Display for Foo {}`, `impl Display for u8 {}`,
+        // `impl Display for &Foo {}`, `impl Display for [Foo] {}` and `impl<T> Display for T {}`
+        let display = || Some(TraitRef { path: display_path, hir_ref_id: HirId::INVALID });
+        let self_tys = [&vec_ty, &foo_ty, &u8_ty, &foo_ref, &foo_slice, &t_ty];
+        let impls = self_tys.map(|self_ty| impl_(display(), self_ty));
+        let items: Vec<_> = (1..)
+            .zip(&impls)
+            .map(|(index, impl_)| Item {
+                ident: Ident::empty(),
+                owner_id: owner_id(index),
+                kind: ItemKind::Impl(impl_),
+                span: DUMMY_SP,
+                vis_span: DUMMY_SP,
+            })
+            .collect();
+        let infos: Vec<_> =
+            items.iter().map(|item| owner_info(Some(Node::Item(item)), &[])).collect();
+        let infos: Vec<_> = infos.iter().collect();
+        let krate = krate(&infos);
+
+        let foreign: Vec<_> = krate.foreign_impls().map(|item| item.owner_id).collect();
+        assert_eq!(foreign, [owner_id(1), owner_id(3), owner_id(5), owner_id(6)]);
+    })
+}
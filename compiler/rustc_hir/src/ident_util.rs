@@ -0,0 +1,10 @@
+//! Helpers for the identifiers stored in the HIR.
+
+use rustc_span::symbol::Ident;
+
+/// Whether `ident` collides with a keyword reserved in its edition, so that it has to be
+/// written as a raw identifier like `r#type`. Keywords that can't be raw identifiers, such as
+/// `self` or `crate`, never need it.
+pub fn ident_needs_raw(ident: Ident) -> bool {
+    ident.is_raw_guess()
+}
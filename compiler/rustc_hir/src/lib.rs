@@ -33,6 +33,7 @@
 pub use rustc_span::def_id;
 mod hir;
 pub mod hir_id;
+pub mod ident_util;
 pub mod intravisit;
 pub mod lang_items;
 pub mod pat_util;
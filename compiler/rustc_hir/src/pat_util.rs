@@ -97,6 +97,29 @@ pub fn simple_ident(&self) -> Option<Ident> {
         }
     }
 
+    /// Returns the ident bound by this pattern if it is a binding, with or without a
+    /// subpattern (`x` or `x @ Some(_)`). Unlike `simple_ident`, this also accepts
+    /// `ref` bindings.
+    pub fn binding_ident(&self) -> Option<Ident> {
+        match self.kind {
+            PatKind::Binding(_, _, ident, _) => Some(ident),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of variables bound by this pattern. The bindings for the same
+    /// variable in the alternatives of an or-pattern, like both `x` in `A(x) | B(x)`, share
+    /// a canonical `HirId` and are only counted once.
+    pub fn count_bindings(&self) -> usize {
+        let mut canonical_ids = FxHashSet::default();
+        self.walk_always(|p| {
+            if let PatKind::Binding(_, canonical_id, _, _) = p.kind {
+                canonical_ids.insert(canonical_id);
+            }
+        });
+        canonical_ids.len()
+    }
+
     /// Returns variants that are necessary to exist for the pattern to match.
     pub fn necessary_variants(&self) -> Vec<DefId> {
         let mut variants = vec![];
@@ -1,8 +1,10 @@
 use crate::definitions::{DefKey, DefPathData, DisambiguatedDefPathData};
+use crate::ident_util::ident_needs_raw;
 use rustc_data_structures::stable_hasher::Hash64;
 use rustc_span::def_id::{DefPathHash, StableCrateId};
 use rustc_span::edition::Edition;
-use rustc_span::{create_session_if_not_set_then, Symbol};
+use rustc_span::symbol::Ident;
+use rustc_span::{create_default_session_globals_then, create_session_if_not_set_then, Symbol};
 
 #[test]
 fn def_path_hash_depends_on_crate_id() {
@@ -39,3 +41,13 @@ fn mk_test_hash(stable_crate_id: StableCrateId) -> DefPathHash {
         }
     })
 }
+
+#[test]
+fn keyword_idents_need_raw() {
+    create_default_session_globals_then(|| {
+        assert!(ident_needs_raw(Ident::from_str("type")));
+        assert!(ident_needs_raw(Ident::from_str("match")));
+        assert!(!ident_needs_raw(Ident::from_str("foo")));
+        assert!(!ident_needs_raw(Ident::from_str("self")));
+    })
+}
@@ -6,19 +6,23 @@
 use rustc_ast::util::parser::{self, AssocOp, Fixity};
 use rustc_ast_pretty::pp::Breaks::{Consistent, Inconsistent};
 use rustc_ast_pretty::pp::{self, Breaks};
+use rustc_ast_pretty::pprust::state::doc_comment_to_string;
 use rustc_ast_pretty::pprust::{Comments, PrintState};
 use rustc_hir as hir;
 use rustc_hir::LifetimeParamKind;
 use rustc_hir::{BindingAnnotation, ByRef, GenericArg, GenericParam, GenericParamKind, Node, Term};
 use rustc_hir::{GenericBound, PatKind, RangeEnd, TraitBoundModifier};
 use rustc_span::source_map::SourceMap;
-use rustc_span::symbol::{kw, Ident, IdentPrinter, Symbol};
+use rustc_span::symbol::{kw, sym, Ident, IdentPrinter, Symbol};
 use rustc_span::{self, FileName};
 use rustc_target::spec::abi::Abi;
 
 use std::cell::Cell;
 use std::vec;
 
+#[cfg(test)]
+mod tests;
+
 pub fn id_to_string(map: &dyn rustc_hir::intravisit::Map<'_>, hir_id: hir::HirId) -> String {
     to_string(&map, |s| s.print_node(map.find(hir_id).unwrap()))
 }
@@ -40,6 +44,8 @@ pub enum Nested {
     ForeignItem(hir::ForeignItemId),
     Body(hir::BodyId),
     BodyParamPat(hir::BodyId, usize),
+    /// The body of a const generic argument, printed with `State::print_const_arg_value`.
+    ConstArg(hir::BodyId),
 }
 
 pub trait PpAnn {
@@ -63,6 +69,7 @@ fn nested(&self, state: &mut State<'_>, nested: Nested) {
             Nested::ForeignItem(id) => state.print_foreign_item(self.foreign_item(id)),
             Nested::Body(id) => state.print_expr(&self.body(id).value),
             Nested::BodyParamPat(id, i) => state.print_pat(self.body(id).params[i].pat),
+            Nested::ConstArg(id) => state.print_const_arg_value(&self.body(id).value),
         }
     }
 }
@@ -72,6 +79,15 @@ pub struct State<'a> {
     comments: Option<Comments<'a>>,
     attrs: &'a dyn Fn(hir::HirId) -> &'a [ast::Attribute],
     ann: &'a (dyn PpAnn + 'a),
+    /// Print `#[doc = "..."]` attributes as `///` (or `//!`) doc comments.
+    pub doc_comments_sugared: bool,
+    /// Always print the self type of a type-relative path in angle brackets (`<T>::Assoc`
+    /// rather than `T::Assoc`). The HIR does not record which trait such a path goes through,
+    /// so this cannot print `<T as Trait>::Assoc`; only paths written that way print so.
+    pub bracket_qself: bool,
+    /// Print desugared constructs, such as `?`, in their surface syntax when it can be
+    /// recovered from the HIR.
+    pub sugared: bool,
 }
 
 impl<'a> State<'a> {
@@ -141,6 +157,39 @@ fn print_ident(&mut self, ident: Ident) {
     fn print_generic_args(&mut self, _: &ast::GenericArgs, _colons_before_params: bool) {
         panic!("AST generic args printed by HIR pretty-printer");
     }
+
+    fn print_attribute_inline(&mut self, attr: &ast::Attribute, is_inline: bool) {
+        if !is_inline {
+            self.hardbreak_if_not_bol();
+        }
+        self.maybe_print_comment(attr.span.lo());
+        match &attr.kind {
+            ast::AttrKind::Normal(normal) => {
+                if self.doc_comments_sugared {
+                    if let Some(data) = attr.doc_str() {
+                        let comment_kind = if data.as_str().contains('\n') {
+                            ast::token::CommentKind::Block
+                        } else {
+                            ast::token::CommentKind::Line
+                        };
+                        self.word(doc_comment_to_string(comment_kind, attr.style, data));
+                        self.hardbreak();
+                        return;
+                    }
+                }
+                match attr.style {
+                    ast::AttrStyle::Inner => self.word("#!["),
+                    ast::AttrStyle::Outer => self.word("#["),
+                }
+                self.print_attr_args(&normal.item, attr.span);
+                self.word("]");
+            }
+            ast::AttrKind::DocComment(comment_kind, data) => {
+                self.word(doc_comment_to_string(*comment_kind, attr.style, *data));
+                self.hardbreak()
+            }
+        }
+    }
 }
 
 pub const INDENT_UNIT: isize = 4;
@@ -178,19 +227,39 @@ pub fn new_from_input(
             comments: Some(Comments::new(sm, filename, input)),
             attrs,
             ann,
+            doc_comments_sugared: false,
+            bracket_qself: false,
+            sugared: false,
         }
     }
 
     fn attrs(&self, id: hir::HirId) -> &'a [ast::Attribute] {
         (self.attrs)(id)
     }
+
+    /// Prints the path and arguments of an attribute. Arguments that form a meta-item tree,
+    /// like `cfg(feature = "x")`, are printed from that tree; others are printed as tokens.
+    pub fn print_attr_args(&mut self, item: &ast::AttrItem, span: rustc_span::Span) {
+        match item.meta(span) {
+            Some(meta) => self.print_meta_item(&meta),
+            None => self.print_attr_item(item, span),
+        }
+    }
 }
 
 pub fn to_string<F>(ann: &dyn PpAnn, f: F) -> String
 where
     F: FnOnce(&mut State<'_>),
 {
-    let mut printer = State { s: pp::Printer::new(), comments: None, attrs: &|_| &[], ann };
+    let mut printer = State {
+        s: pp::Printer::new(),
+        comments: None,
+        attrs: &|_| &[],
+        ann,
+        doc_comments_sugared: false,
+        bracket_qself: false,
+        sugared: false,
+    };
     f(&mut printer);
     printer.s.eof()
 }
@@ -327,8 +396,12 @@ pub fn print_type(&mut self, ty: &hir::Ty<'_>) {
             hir::TyKind::OpaqueDef(..) => self.word("/*impl Trait*/"),
             hir::TyKind::Path(ref qpath) => self.print_qpath(qpath, false),
             hir::TyKind::TraitObject(bounds, ref lifetime, syntax) => {
-                if syntax == ast::TraitObjectSyntax::Dyn {
-                    self.word_space("dyn");
+                // Bare trait objects from the 2015 edition are printed with `dyn` too.
+                match syntax {
+                    ast::TraitObjectSyntax::Dyn | ast::TraitObjectSyntax::None => {
+                        self.word_space("dyn")
+                    }
+                    ast::TraitObjectSyntax::DynStar => self.word_space("dyn*"),
                 }
                 let mut first = true;
                 for bound in bounds {
@@ -1029,6 +1102,22 @@ pub fn print_anon_const(&mut self, constant: &hir::AnonConst) {
         self.ann.nested(self, Nested::Body(constant.body))
     }
 
+    /// Prints the value of a const generic argument. Anything but a block, a path or a
+    /// (possibly negated) literal has to be wrapped in braces to be a valid argument.
+    pub fn print_const_arg_value(&mut self, value: &hir::Expr<'_>) {
+        match value.kind {
+            hir::ExprKind::Lit(_)
+            | hir::ExprKind::Unary(hir::UnOp::Neg, hir::Expr { kind: hir::ExprKind::Lit(_), .. })
+            | hir::ExprKind::Path(_)
+            | hir::ExprKind::Block(..) => self.print_expr(value),
+            _ => {
+                self.word("{ ");
+                self.print_expr(value);
+                self.word(" }");
+            }
+        }
+    }
+
     fn print_call_post(&mut self, args: &[hir::Expr<'_>]) {
         self.popen();
         self.commasep_exprs(Inconsistent, args);
@@ -1036,7 +1125,12 @@ fn print_call_post(&mut self, args: &[hir::Expr<'_>]) {
     }
 
     fn print_expr_maybe_paren(&mut self, expr: &hir::Expr<'_>, prec: i8) {
-        self.print_expr_cond_paren(expr, expr.precedence().order() < prec)
+        let order = if self.sugared && hir::is_range_literal(expr) {
+            parser::PREC_RANGE
+        } else {
+            expr.precedence().order()
+        };
+        self.print_expr_cond_paren(expr, order < prec)
     }
 
     /// Prints an expr using syntax that's acceptable in a condition position, such as the `cond` in
@@ -1112,6 +1206,35 @@ fn print_expr_repeat(&mut self, element: &hir::Expr<'_>, count: &hir::ArrayLen)
         self.end()
     }
 
+    /// Prints a range literal in its surface syntax, like `a..b` or `a..=b`. The HIR lowers
+    /// `a..=b` to a call to `RangeInclusive::new` and all other ranges to struct literals.
+    fn print_expr_range(&mut self, expr: &hir::Expr<'_>) {
+        let (start, end, limits) = match expr.kind {
+            hir::ExprKind::Call(_, [start, end]) => (Some(start), Some(end), "..="),
+            hir::ExprKind::Struct(qpath, fields, _) => {
+                let field = |name: Symbol| {
+                    fields.iter().find(|field| field.ident.name == name).map(|field| field.expr)
+                };
+                let limits = match qpath {
+                    hir::QPath::LangItem(hir::LangItem::RangeToInclusive, ..) => "..=",
+                    _ => "..",
+                };
+                (field(sym::start), field(sym::end), limits)
+            }
+            _ => panic!("expected a range literal"),
+        };
+        // Same fake precedence as in `rustc_ast_pretty`: any operand with lower precedence
+        // than a binop gets parenthesized.
+        let fake_prec = AssocOp::LOr.precedence() as i8;
+        if let Some(start) = start {
+            self.print_expr_maybe_paren(start, fake_prec);
+        }
+        self.word(limits);
+        if let Some(end) = end {
+            self.print_expr_maybe_paren(end, fake_prec);
+        }
+    }
+
     fn print_expr_struct(
         &mut self,
         qpath: &hir::QPath<'_>,
@@ -1181,10 +1304,7 @@ fn print_expr_method_call(
         self.word(".");
         self.print_ident(segment.ident);
 
-        let generic_args = segment.args();
-        if !generic_args.args.is_empty() || !generic_args.bindings.is_empty() {
-            self.print_generic_args(generic_args, true);
-        }
+        self.print_segment_generic_args(segment, true);
 
         self.print_call_post(base_args)
     }
@@ -1375,6 +1495,11 @@ pub fn print_expr(&mut self, expr: &hir::Expr<'_>) {
             hir::ExprKind::Repeat(element, ref count) => {
                 self.print_expr_repeat(element, count);
             }
+            hir::ExprKind::Struct(..) | hir::ExprKind::Call(..)
+                if self.sugared && hir::is_range_literal(expr) =>
+            {
+                self.print_expr_range(expr);
+            }
             hir::ExprKind::Struct(qpath, fields, wth) => {
                 self.print_expr_struct(qpath, fields, wth);
             }
@@ -1450,6 +1575,15 @@ pub fn print_expr(&mut self, expr: &hir::Expr<'_>) {
                 self.head("loop");
                 self.print_block(blk);
             }
+            hir::ExprKind::Match(
+                hir::Expr { kind: hir::ExprKind::Call(_, [expr]), .. },
+                _,
+                hir::MatchSource::TryDesugar,
+            ) if self.sugared => {
+                // `match Try::branch(expr) { .. }`
+                self.print_expr_maybe_paren(expr, parser::PREC_POSTFIX);
+                self.word("?");
+            }
             hir::ExprKind::Match(expr, arms, _) => {
                 self.cbox(INDENT_UNIT);
                 self.ibox(INDENT_UNIT);
@@ -1609,7 +1743,7 @@ pub fn print_path<R>(&mut self, path: &hir::Path<'_, R>, colons_before_params: b
             }
             if segment.ident.name != kw::PathRoot {
                 self.print_ident(segment.ident);
-                self.print_generic_args(segment.args(), colons_before_params);
+                self.print_segment_generic_args(segment, colons_before_params);
             }
         }
     }
@@ -1617,7 +1751,28 @@ pub fn print_path<R>(&mut self, path: &hir::Path<'_, R>, colons_before_params: b
     pub fn print_path_segment(&mut self, segment: &hir::PathSegment<'_>) {
         if segment.ident.name != kw::PathRoot {
             self.print_ident(segment.ident);
-            self.print_generic_args(segment.args(), false);
+            self.print_segment_generic_args(segment, false);
+        }
+    }
+
+    /// Prints the generic arguments of `segment`. An empty argument list is only printed
+    /// (as `<>` or `::<>`) if it was written in the source, which is recorded by its span.
+    /// `infer_args` cannot tell, as lowering sets it for both `Vec::new` and `Vec::<>::new`.
+    fn print_segment_generic_args(
+        &mut self,
+        segment: &hir::PathSegment<'_>,
+        colons_before_params: bool,
+    ) {
+        match segment.args {
+            Some(args)
+                if args.args.is_empty()
+                    && args.bindings.is_empty()
+                    && args.parenthesized == hir::GenericArgsParentheses::No
+                    && args.span_ext().is_some() =>
+            {
+                self.word(if colons_before_params { "::<>" } else { "<>" })
+            }
+            _ => self.print_generic_args(segment.args(), colons_before_params),
         }
     }
 
@@ -1636,7 +1791,7 @@ pub fn print_qpath(&mut self, qpath: &hir::QPath<'_>, colons_before_params: bool
                     }
                     if segment.ident.name != kw::PathRoot {
                         self.print_ident(segment.ident);
-                        self.print_generic_args(segment.args(), colons_before_params);
+                        self.print_segment_generic_args(segment, colons_before_params);
                     }
                 }
 
@@ -1644,13 +1799,15 @@ pub fn print_qpath(&mut self, qpath: &hir::QPath<'_>, colons_before_params: bool
                 self.word("::");
                 let item_segment = path.segments.last().unwrap();
                 self.print_ident(item_segment.ident);
-                self.print_generic_args(item_segment.args(), colons_before_params)
+                self.print_segment_generic_args(item_segment, colons_before_params)
             }
             hir::QPath::TypeRelative(qself, item_segment) => {
                 // If we've got a compound-qualified-path, let's push an additional pair of angle
                 // brackets, so that we pretty-print `<<A::B>::C>` as `<A::B>::C`, instead of just
                 // `A::B::C` (since the latter could be ambiguous to the user)
-                if let hir::TyKind::Path(hir::QPath::Resolved(None, _)) = qself.kind {
+                let is_plain_path =
+                    matches!(qself.kind, hir::TyKind::Path(hir::QPath::Resolved(None, _)));
+                if is_plain_path && !self.bracket_qself {
                     self.print_type(qself);
                 } else {
                     self.word("<");
@@ -1660,7 +1817,7 @@ pub fn print_qpath(&mut self, qpath: &hir::QPath<'_>, colons_before_params: bool
 
                 self.word("::");
                 self.print_ident(item_segment.ident);
-                self.print_generic_args(item_segment.args(), colons_before_params)
+                self.print_segment_generic_args(item_segment, colons_before_params)
             }
             hir::QPath::LangItem(lang_item, span, _) => {
                 self.word("#[lang = \"");
@@ -1708,7 +1865,9 @@ fn print_generic_args(
                             GenericArg::Lifetime(lt) if !elide_lifetimes => s.print_lifetime(lt),
                             GenericArg::Lifetime(_) => {}
                             GenericArg::Type(ty) => s.print_type(ty),
-                            GenericArg::Const(ct) => s.print_anon_const(&ct.value),
+                            GenericArg::Const(ct) => {
+                                s.ann.nested(s, Nested::ConstArg(ct.value.body))
+                            }
                             GenericArg::Infer(_inf) => s.word("_"),
                         }
                     });
@@ -1994,17 +2153,21 @@ pub fn print_fn(
         assert!(arg_names.is_empty() || body_id.is_none());
         self.commasep(Inconsistent, decl.inputs, |s, ty| {
             s.ibox(INDENT_UNIT);
-            if let Some(arg_name) = arg_names.get(i) {
-                s.word(arg_name.to_string());
-                s.word(":");
-                s.space();
-            } else if let Some(body_id) = body_id {
-                s.ann.nested(s, Nested::BodyParamPat(body_id, i));
-                s.word(":");
-                s.space();
+            if i == 0 && decl.implicit_self.has_implicit_self() {
+                s.print_implicit_self(decl.implicit_self, ty);
+            } else {
+                if let Some(arg_name) = arg_names.get(i) {
+                    s.word(arg_name.to_string());
+                    s.word(":");
+                    s.space();
+                } else if let Some(body_id) = body_id {
+                    s.ann.nested(s, Nested::BodyParamPat(body_id, i));
+                    s.word(":");
+                    s.space();
+                }
+                s.print_type(ty);
             }
             i += 1;
-            s.print_type(ty);
             s.end()
         });
         if decl.c_variadic {
@@ -2016,6 +2179,25 @@ pub fn print_fn(
         self.print_where_clause(generics)
     }
 
+    /// Prints a `self` parameter in its shorthand form (`self`, `mut self`, `&'a self` or
+    /// `&mut self`), given the type it was lowered to.
+    fn print_implicit_self(&mut self, kind: hir::ImplicitSelfKind, ty: &hir::Ty<'_>) {
+        match kind {
+            hir::ImplicitSelfKind::Mut => self.word_nbsp("mut"),
+            hir::ImplicitSelfKind::ImmRef | hir::ImplicitSelfKind::MutRef => {
+                if let hir::TyKind::Ref(lifetime, mt) = ty.kind {
+                    self.word("&");
+                    self.print_opt_lifetime(lifetime);
+                    if mt.mutbl.is_mut() {
+                        self.word_nbsp("mut");
+                    }
+                }
+            }
+            hir::ImplicitSelfKind::Imm | hir::ImplicitSelfKind::None => {}
+        }
+        self.word("self")
+    }
+
     fn print_closure_params(&mut self, decl: &hir::FnDecl<'_>, body_id: hir::BodyId) {
         self.word("|");
         let mut i = 0;
@@ -2133,10 +2315,14 @@ pub fn print_bounds<'b>(
     }
 
     pub fn print_generic_params(&mut self, generic_params: &[GenericParam<'_>]) {
+        // The synthetic parameters of argument-position `impl Trait` are printed as
+        // `impl Trait` where they are used instead.
+        let generic_params: Vec<_> =
+            generic_params.iter().filter(|param| !param.is_impl_trait()).collect();
         if !generic_params.is_empty() {
             self.word("<");
 
-            self.commasep(Inconsistent, generic_params, |s, param| s.print_generic_param(param));
+            self.commasep(Inconsistent, &generic_params, |s, param| s.print_generic_param(param));
 
             self.word(">");
         }
@@ -2175,14 +2361,28 @@ pub fn print_lifetime(&mut self, lifetime: &hir::Lifetime) {
     }
 
     pub fn print_where_clause(&mut self, generics: &hir::Generics<'_>) {
-        if generics.predicates.is_empty() {
+        // The bounds of argument-position `impl Trait` are already part of its name.
+        let predicates: Vec<_> = generics
+            .predicates
+            .iter()
+            .filter(|predicate| {
+                !matches!(
+                    predicate,
+                    hir::WherePredicate::BoundPredicate(hir::WhereBoundPredicate {
+                        origin: hir::PredicateOrigin::ImplTrait,
+                        ..
+                    })
+                )
+            })
+            .collect();
+        if predicates.is_empty() {
             return;
         }
 
         self.space();
         self.word_space("where");
 
-        for (i, predicate) in generics.predicates.iter().enumerate() {
+        for (i, predicate) in predicates.into_iter().enumerate() {
             if i != 0 {
                 self.word_space(",");
             }
@@ -2244,7 +2444,20 @@ pub fn print_mutability(&mut self, mutbl: hir::Mutability, print_const: bool) {
 
     pub fn print_mt(&mut self, mt: &hir::MutTy<'_>, print_const: bool) {
         self.print_mutability(mt.mutbl, print_const);
-        self.print_type(mt.ty);
+        // `&dyn Foo + Send` would parse as `(&dyn Foo) + Send`.
+        let needs_parens = match mt.ty.kind {
+            hir::TyKind::TraitObject(bounds, lifetime, _) => {
+                bounds.len() > 1 || !lifetime.is_elided()
+            }
+            _ => false,
+        };
+        if needs_parens {
+            self.popen();
+            self.print_type(mt.ty);
+            self.pclose();
+        } else {
+            self.print_type(mt.ty);
+        }
     }
 
     pub fn print_fn_output(&mut self, decl: &hir::FnDecl<'_>) {
@@ -0,0 +1,743 @@
+use super::*;
+
+use rustc_arena::DroplessArena;
+use rustc_ast::attr::{mk_attr, mk_attr_name_value_str, mk_attr_nested_word, AttrIdGenerator};
+use rustc_ast::token;
+use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_span::create_default_session_globals_then;
+use rustc_span::symbol::sym;
+use rustc_span::{BytePos, Span, DUMMY_SP};
+
+fn item_to_string(
+    item: &hir::Item<'_>,
+    attrs: &[ast::Attribute],
+    doc_comments_sugared: bool,
+) -> String {
+    let attrs = move |_: hir::HirId| attrs;
+    let mut printer = State {
+        s: pp::Printer::new(),
+        comments: None,
+        attrs: &attrs,
+        ann: NO_ANN,
+        doc_comments_sugared,
+        bracket_qself: false,
+        sugared: false,
+    };
+    printer.print_item(item);
+    printer.s.eof()
+}
+
+fn with_fn_item<R>(name: &str, f: impl FnOnce(&hir::Item<'_>) -> R) -> R {
+    let decl = fn_decl(&[], hir::ImplicitSelfKind::None);
+    let item = hir::Item {
+        ident: Ident::from_str(name),
+        owner_id: hir::CRATE_OWNER_ID,
+        kind: hir::ItemKind::Fn(
+            hir::FnSig { header: fn_header(), decl: &decl, span: DUMMY_SP },
+            hir::Generics::empty(),
+            hir::BodyId { hir_id: hir::CRATE_HIR_ID },
+        ),
+        span: DUMMY_SP,
+        vis_span: DUMMY_SP,
+    };
+    f(&item)
+}
+
+fn doc_attr(style: ast::AttrStyle, doc: &str) -> ast::Attribute {
+    mk_attr_name_value_str(&AttrIdGenerator::new(), style, sym::doc, Symbol::intern(doc), DUMMY_SP)
+}
+
+#[test]
+fn test_doc_comments_sugared() {
+    create_default_session_globals_then(|| {
+        let attrs = [doc_attr(ast::AttrStyle::Outer, " Frobnicates the widget.")];
+        with_fn_item("frob", |item| {
+            assert_eq!(
+                item_to_string(item, &attrs, true).trim_end(),
+                "/// Frobnicates the widget.\nfn frob()"
+            );
+            assert_eq!(
+                item_to_string(item, &attrs, false).trim_end(),
+                "#[doc = \" Frobnicates the widget.\"]\nfn frob()"
+            );
+        });
+    })
+}
+
+#[test]
+fn test_doc_comments_sugared_multiline() {
+    create_default_session_globals_then(|| {
+        let attrs = [doc_attr(ast::AttrStyle::Outer, "\n Frobnicates\n the widget.\n")];
+        with_fn_item("frob", |item| {
+            assert_eq!(
+                item_to_string(item, &attrs, true).trim_end(),
+                "/**\n Frobnicates\n the widget.\n*/\nfn frob()"
+            );
+        });
+    })
+}
+
+fn ty<'hir>(kind: hir::TyKind<'hir>) -> hir::Ty<'hir> {
+    hir::Ty { hir_id: hir::HirId::INVALID, kind, span: DUMMY_SP }
+}
+
+fn with_prim_ty<R>(name: &str, prim: hir::PrimTy, f: impl FnOnce(&hir::Ty<'_>) -> R) -> R {
+    let res = hir::def::Res::PrimTy(prim);
+    let segments = [hir::PathSegment::new(Ident::from_str(name), hir::HirId::INVALID, res)];
+    let path = hir::Path { span: DUMMY_SP, res, segments: &segments };
+    f(&ty(hir::TyKind::Path(hir::QPath::Resolved(None, &path))))
+}
+
+fn field<'hir>(name: &str, ty: &'hir hir::Ty<'hir>) -> hir::FieldDef<'hir> {
+    hir::FieldDef {
+        span: DUMMY_SP,
+        vis_span: DUMMY_SP,
+        ident: Ident::from_str(name),
+        hir_id: hir::HirId::INVALID,
+        def_id: hir::def_id::CRATE_DEF_ID,
+        ty,
+    }
+}
+
+#[test]
+fn test_union_item() {
+    create_default_session_globals_then(|| {
+        let u8 = hir::PrimTy::Uint(ast::UintTy::U8);
+        let f32 = hir::PrimTy::Float(ast::FloatTy::F32);
+        with_prim_ty("u8", u8, |u8_ty| {
+            with_prim_ty("f32", f32, |f32_ty| {
+                let fields = [field("a", u8_ty), field("b", f32_ty)];
+                let item = hir::Item {
+                    ident: Ident::from_str("U"),
+                    owner_id: hir::CRATE_OWNER_ID,
+                    kind: hir::ItemKind::Union(
+                        hir::VariantData::Struct(&fields, false),
+                        hir::Generics::empty(),
+                    ),
+                    span: DUMMY_SP,
+                    vis_span: DUMMY_SP,
+                };
+                assert_eq!(
+                    item_to_string(&item, &[], false),
+                    "union U {\n    a: u8,\n    b: f32,\n}"
+                );
+            })
+        })
+    })
+}
+
+fn segment<'hir>(name: &str) -> hir::PathSegment<'hir> {
+    hir::PathSegment::new(Ident::from_str(name), hir::HirId::INVALID, hir::def::Res::Err)
+}
+
+fn path<'hir>(segments: &'hir [hir::PathSegment<'hir>]) -> hir::Path<'hir> {
+    hir::Path { span: DUMMY_SP, res: hir::def::Res::Err, segments }
+}
+
+fn ty_to_string(ty: &hir::Ty<'_>, bracket_qself: bool) -> String {
+    to_string(NO_ANN, |s| {
+        s.bracket_qself = bracket_qself;
+        s.print_type(ty)
+    })
+}
+
+#[test]
+fn test_bracket_qself() {
+    create_default_session_globals_then(|| {
+        let t_segments = [segment("T")];
+        let t_path = path(&t_segments);
+        let t_ty = ty(hir::TyKind::Path(hir::QPath::Resolved(None, &t_path)));
+
+        // `T::Item`
+        let item_segment = segment("Item");
+        let assoc_ty = ty(hir::TyKind::Path(hir::QPath::TypeRelative(&t_ty, &item_segment)));
+        assert_eq!(ty_to_string(&assoc_ty, false), "T::Item");
+        assert_eq!(ty_to_string(&assoc_ty, true), "<T>::Item");
+
+        // `Vec::<T>::Target`
+        let vec_args =
+            hir::GenericArgs { args: &[hir::GenericArg::Type(&t_ty)], ..hir::GenericArgs::none() };
+        let vec_segments = [hir::PathSegment { args: Some(&vec_args), ..segment("Vec") }];
+        let vec_path = path(&vec_segments);
+        let vec_ty = ty(hir::TyKind::Path(hir::QPath::Resolved(None, &vec_path)));
+        let target_segment = segment("Target");
+        let target_ty = ty(hir::TyKind::Path(hir::QPath::TypeRelative(&vec_ty, &target_segment)));
+        assert_eq!(ty_to_string(&target_ty, false), "Vec<T>::Target");
+        assert_eq!(ty_to_string(&target_ty, true), "<Vec<T>>::Target");
+
+        // `<Vec<T> as Clone>::clone` already names its trait, so the option leaves it alone.
+        let clone_segments = [segment("Clone"), segment("clone")];
+        let clone_path = path(&clone_segments);
+        let clone_ty = ty(hir::TyKind::Path(hir::QPath::Resolved(Some(&vec_ty), &clone_path)));
+        assert_eq!(ty_to_string(&clone_ty, true), "<Vec<T> as Clone>::clone");
+    })
+}
+
+fn expr<'hir>(kind: hir::ExprKind<'hir>) -> hir::Expr<'hir> {
+    hir::Expr { hir_id: hir::HirId::INVALID, kind, span: DUMMY_SP }
+}
+
+/// A path expression with a single segment, like `a`.
+fn path_expr<'hir>(arena: &'hir DroplessArena, name: &str) -> hir::Expr<'hir> {
+    let segments = arena.alloc_slice(&[segment(name)]);
+    expr(hir::ExprKind::Path(hir::QPath::Resolved(None, arena.alloc(path(segments)))))
+}
+
+fn int_lit(value: u128) -> hir::Lit {
+    hir::Lit { node: ast::LitKind::Int(value, ast::LitIntType::Unsuffixed), span: DUMMY_SP }
+}
+
+#[test]
+fn test_sugared_try() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let foo = path_expr(&arena, "foo");
+        let foo_call = [expr(hir::ExprKind::Call(&foo, &[]))];
+
+        // `match Try::branch(foo()) {}`, as lowered from `foo()?`
+        let branch = expr(hir::ExprKind::Path(hir::QPath::LangItem(
+            hir::LangItem::TryTraitBranch,
+            DUMMY_SP,
+            None,
+        )));
+        let scrutinee = expr(hir::ExprKind::Call(&branch, &foo_call));
+        let try_match =
+            expr(hir::ExprKind::Match(&scrutinee, &[], hir::MatchSource::TryDesugar));
+
+        let sugared = to_string(NO_ANN, |s| {
+            s.sugared = true;
+            s.print_expr(&try_match)
+        });
+        assert_eq!(sugared, "foo()?");
+        let desugared = to_string(NO_ANN, |s| s.print_expr(&try_match));
+        assert!(desugared.starts_with("match #[lang = \"branch\"](foo())"), "{desugared}");
+    })
+}
+
+fn expr_to_string(expr: &hir::Expr<'_>) -> String {
+    to_string(NO_ANN, |s| s.print_expr(expr))
+}
+
+#[test]
+fn test_negative_literals() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let five = int_lit(5);
+        let five = expr(hir::ExprKind::Lit(&five));
+        assert_eq!(expr_to_string(&expr(hir::ExprKind::Unary(hir::UnOp::Neg, &five))), "-5");
+
+        let float = hir::Lit {
+            node: ast::LitKind::Float(
+                Symbol::intern("5.0"),
+                ast::LitFloatType::Suffixed(ast::FloatTy::F32),
+            ),
+            span: DUMMY_SP,
+        };
+        let float = expr(hir::ExprKind::Lit(&float));
+        assert_eq!(
+            expr_to_string(&expr(hir::ExprKind::Unary(hir::UnOp::Neg, &float))),
+            "-5.0f32"
+        );
+
+        let (a, b) = (path_expr(&arena, "a"), path_expr(&arena, "b"));
+        let add = rustc_span::source_map::Spanned { node: hir::BinOpKind::Add, span: DUMMY_SP };
+        let sum = expr(hir::ExprKind::Binary(add, &a, &b));
+        assert_eq!(expr_to_string(&expr(hir::ExprKind::Unary(hir::UnOp::Neg, &sum))), "-(a + b)");
+    })
+}
+
+fn lifetime(name: &str, res: hir::LifetimeName) -> hir::Lifetime {
+    hir::Lifetime { hir_id: hir::HirId::INVALID, ident: Ident::from_str(name), res }
+}
+
+fn poly_trait_ref<'hir>(path: &'hir hir::Path<'hir>) -> hir::PolyTraitRef<'hir> {
+    hir::PolyTraitRef {
+        bound_generic_params: &[],
+        trait_ref: hir::TraitRef { path, hir_ref_id: hir::HirId::INVALID },
+        span: DUMMY_SP,
+    }
+}
+
+fn ref_ty<'hir>(lifetime: &'hir hir::Lifetime, ty: &'hir hir::Ty<'hir>) -> hir::Ty<'hir> {
+    self::ty(hir::TyKind::Ref(lifetime, hir::MutTy { ty, mutbl: hir::Mutability::Not }))
+}
+
+#[test]
+fn test_ref_to_trait_object() {
+    create_default_session_globals_then(|| {
+        let foo_segments = [segment("Foo")];
+        let foo_path = path(&foo_segments);
+        let send_segments = [segment("Send")];
+        let send_path = path(&send_segments);
+        let object_lifetime = lifetime("", hir::LifetimeName::ImplicitObjectLifetimeDefault);
+        let ref_lifetime = lifetime("", hir::LifetimeName::Infer);
+
+        // `&dyn Foo`
+        let foo_bounds = [poly_trait_ref(&foo_path)];
+        let foo = ty(hir::TyKind::TraitObject(
+            &foo_bounds,
+            &object_lifetime,
+            ast::TraitObjectSyntax::Dyn,
+        ));
+        assert_eq!(ty_to_string(&ref_ty(&ref_lifetime, &foo), false), "&dyn Foo");
+
+        // `&(dyn Foo + Send)`
+        let foo_send_bounds = [poly_trait_ref(&foo_path), poly_trait_ref(&send_path)];
+        let foo_send = ty(hir::TyKind::TraitObject(
+            &foo_send_bounds,
+            &object_lifetime,
+            ast::TraitObjectSyntax::Dyn,
+        ));
+        assert_eq!(ty_to_string(&ref_ty(&ref_lifetime, &foo_send), false), "&(dyn Foo + Send)");
+
+        // `Foo + Send`, a bare trait object
+        let bare = ty(hir::TyKind::TraitObject(
+            &foo_send_bounds,
+            &object_lifetime,
+            ast::TraitObjectSyntax::None,
+        ));
+        assert_eq!(ty_to_string(&bare, false), "dyn Foo + Send");
+    })
+}
+
+/// Prints the value of every const argument as `value`.
+struct ConstArgAnn<'a>(&'a hir::Expr<'a>);
+
+impl PpAnn for ConstArgAnn<'_> {
+    fn nested(&self, state: &mut State<'_>, nested: Nested) {
+        if let Nested::ConstArg(_) = nested {
+            state.print_const_arg_value(self.0)
+        }
+    }
+}
+
+fn const_arg_ty_to_string(value: &hir::Expr<'_>) -> String {
+    let const_arg = hir::ConstArg {
+        value: hir::AnonConst {
+            hir_id: hir::HirId::INVALID,
+            def_id: hir::def_id::CRATE_DEF_ID,
+            body: hir::BodyId { hir_id: hir::HirId::INVALID },
+        },
+        span: DUMMY_SP,
+    };
+    let const_args = [hir::GenericArg::Const(const_arg)];
+    let args = hir::GenericArgs { args: &const_args, ..hir::GenericArgs::none() };
+    let foo_segments = [hir::PathSegment { args: Some(&args), ..segment("Foo") }];
+    let foo_path = path(&foo_segments);
+    let foo = ty(hir::TyKind::Path(hir::QPath::Resolved(None, &foo_path)));
+    to_string(&ConstArgAnn(value), |s| s.print_type(&foo))
+}
+
+#[test]
+fn test_const_arg_braces() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+
+        // `Foo<3>`
+        let three = int_lit(3);
+        let three = expr(hir::ExprKind::Lit(&three));
+        assert_eq!(const_arg_ty_to_string(&three), "Foo<3>");
+
+        // `Foo<{ N + 1 }>`
+        let n = path_expr(&arena, "N");
+        let one = int_lit(1);
+        let one = expr(hir::ExprKind::Lit(&one));
+        let add = rustc_span::source_map::Spanned { node: hir::BinOpKind::Add, span: DUMMY_SP };
+        let sum = expr(hir::ExprKind::Binary(add, &n, &one));
+        assert_eq!(const_arg_ty_to_string(&sum), "Foo<{ N + 1 }>");
+        assert_eq!(const_arg_ty_to_string(&n), "Foo<N>");
+    })
+}
+
+fn vec_new_to_string(vec: hir::PathSegment<'_>) -> String {
+    let segments = [vec, segment("new")];
+    let path = path(&segments);
+    expr_to_string(&expr(hir::ExprKind::Path(hir::QPath::Resolved(None, &path))))
+}
+
+#[test]
+fn test_inferred_generic_args() {
+    create_default_session_globals_then(|| {
+        // `Vec::new`, lowered without arguments.
+        assert_eq!(vec_new_to_string(segment("Vec")), "Vec::new");
+
+        // `Vec::<u8>::new`
+        let span_ext = Span::with_root_ctxt(BytePos(3), BytePos(9));
+        with_prim_ty("u8", hir::PrimTy::Uint(ast::UintTy::U8), |u8_ty| {
+            let type_args = [hir::GenericArg::Type(u8_ty)];
+            let args = hir::GenericArgs { args: &type_args, span_ext, ..hir::GenericArgs::none() };
+            let vec = hir::PathSegment { args: Some(&args), infer_args: false, ..segment("Vec") };
+            assert_eq!(vec_new_to_string(vec), "Vec::<u8>::new");
+        });
+
+        // `Vec::<>::new` still infers its arguments, but the empty list has a span.
+        let span_ext = Span::with_root_ctxt(BytePos(3), BytePos(7));
+        let args = hir::GenericArgs { span_ext, ..hir::GenericArgs::none() };
+        let vec = hir::PathSegment { args: Some(&args), infer_args: true, ..segment("Vec") };
+        assert_eq!(vec_new_to_string(vec), "Vec::<>::new");
+
+        // An empty argument list without a span was not written, so it is not printed.
+        let args = hir::GenericArgs::none();
+        let vec = hir::PathSegment { args: Some(&args), infer_args: true, ..segment("Vec") };
+        assert_eq!(vec_new_to_string(vec), "Vec::new");
+    })
+}
+
+fn block<'hir>(expr: &'hir hir::Expr<'hir>) -> hir::Block<'hir> {
+    hir::Block {
+        stmts: &[],
+        expr: Some(expr),
+        hir_id: hir::HirId::INVALID,
+        rules: hir::BlockCheckMode::DefaultBlock,
+        span: DUMMY_SP,
+        targeted_by_break: false,
+    }
+}
+
+#[test]
+fn test_sugared_if_else_chain() {
+    create_default_session_globals_then(|| {
+        let arena = DroplessArena::default();
+        let (a, b) = (path_expr(&arena, "a"), path_expr(&arena, "b"));
+        let lits = [0, 1, 2].map(int_lit);
+        let values = [
+            expr(hir::ExprKind::Lit(&lits[0])),
+            expr(hir::ExprKind::Lit(&lits[1])),
+            expr(hir::ExprKind::Lit(&lits[2])),
+        ];
+        let blocks = [block(&values[0]), block(&values[1]), block(&values[2])];
+        let branches = [
+            expr(hir::ExprKind::Block(&blocks[0], None)),
+            expr(hir::ExprKind::Block(&blocks[1], None)),
+            expr(hir::ExprKind::Block(&blocks[2], None)),
+        ];
+
+        // `if a { 0 } else if b { 1 } else { 2 }`
+        let else_if = expr(hir::ExprKind::If(&b, &branches[1], Some(&branches[2])));
+        let chain = expr(hir::ExprKind::If(&a, &branches[0], Some(&else_if)));
+        let printed = to_string(NO_ANN, |s| {
+            s.sugared = true;
+            s.print_expr(&chain)
+        });
+        assert_eq!(printed, "if a { 0 } else if b { 1 } else { 2 }");
+    })
+}
+
+#[test]
+fn test_type_alias_impl_trait() {
+    create_default_session_globals_then(|| {
+        let bar_segments = [segment("Bar")];
+        let bar_path = path(&bar_segments);
+        let bounds = [hir::GenericBound::Trait(
+            poly_trait_ref(&bar_path),
+            hir::TraitBoundModifier::None,
+        )];
+        let opaque_ty = hir::OpaqueTy {
+            generics: hir::Generics::empty(),
+            bounds: &bounds,
+            origin: hir::OpaqueTyOrigin::TyAlias { in_assoc_ty: false },
+            in_trait: false,
+        };
+        let item = hir::Item {
+            ident: Ident::from_str("Foo"),
+            owner_id: hir::CRATE_OWNER_ID,
+            kind: hir::ItemKind::OpaqueTy(opaque_ty),
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        assert_eq!(item_to_string(&item, &[], false), "type Foo = impl Bar;");
+    })
+}
+
+/// Prints the value of every body as `value`.
+struct BodyAnn<'a>(&'a hir::Expr<'a>);
+
+impl PpAnn for BodyAnn<'_> {
+    fn nested(&self, state: &mut State<'_>, nested: Nested) {
+        if let Nested::Body(_) = nested {
+            state.print_expr(self.0)
+        }
+    }
+}
+
+#[test]
+fn test_trait_const_default() {
+    create_default_session_globals_then(|| {
+        let one = int_lit(1);
+        let one = expr(hir::ExprKind::Lit(&one));
+        with_prim_ty("u8", hir::PrimTy::Uint(ast::UintTy::U8), |u8_ty| {
+            let trait_const = |default| hir::TraitItem {
+                ident: Ident::from_str("N"),
+                owner_id: hir::CRATE_OWNER_ID,
+                generics: hir::Generics::empty(),
+                kind: hir::TraitItemKind::Const(u8_ty, default),
+                span: DUMMY_SP,
+                defaultness: hir::Defaultness::Default { has_value: default.is_some() },
+            };
+
+            // `const N: u8 = 1;`
+            let with_default = trait_const(Some(hir::BodyId { hir_id: hir::HirId::INVALID }));
+            let printed = to_string(&BodyAnn(&one), |s| s.print_trait_item(&with_default));
+            assert_eq!(printed, "const N: u8 = 1;");
+
+            // `const N: u8;`
+            let without_default = trait_const(None);
+            let printed = to_string(&BodyAnn(&one), |s| s.print_trait_item(&without_default));
+            assert_eq!(printed, "const N: u8;");
+        })
+    })
+}
+
+#[test]
+fn test_empty_where_clause() {
+    create_default_session_globals_then(|| {
+        let generics = hir::Generics::empty();
+        assert!(generics.predicates.is_empty());
+        assert_eq!(to_string(NO_ANN, |s| s.print_where_clause(generics)), "");
+
+        with_fn_item("frob", |item| {
+            assert!(!item_to_string(item, &[], false).contains("where"));
+        });
+    })
+}
+
+#[test]
+fn test_attr_args() {
+    create_default_session_globals_then(|| {
+        let g = AttrIdGenerator::new();
+        let style = ast::AttrStyle::Outer;
+        let cfg_test = mk_attr_nested_word(&g, style, sym::cfg, sym::test, DUMMY_SP);
+        with_fn_item("frob", |item| {
+            assert_eq!(
+                item_to_string(item, &[cfg_test], false).trim_end(),
+                "#[cfg(test)]\nfn frob()"
+            );
+        });
+
+        // `#[allow(clippy::all)]`, whose tokens alone print as `allow(clippy :: all)`.
+        let ident = |name| TokenTree::token_alone(token::Ident(name, false), DUMMY_SP);
+        let tokens = TokenStream::new(vec![
+            ident(sym::clippy),
+            TokenTree::token_alone(token::ModSep, DUMMY_SP),
+            ident(sym::all),
+        ]);
+        let args = ast::AttrArgs::Delimited(ast::DelimArgs {
+            dspan: DelimSpan::from_single(DUMMY_SP),
+            delim: ast::MacDelimiter::Parenthesis,
+            tokens,
+        });
+        let allow_path = ast::Path::from_ident(Ident::with_dummy_span(sym::allow));
+        let allow = mk_attr(&g, style, allow_path, args, DUMMY_SP);
+        let ast::AttrKind::Normal(normal) = &allow.kind else { unreachable!() };
+        let from_tokens = to_string(NO_ANN, |s| s.print_attr_item(&normal.item, DUMMY_SP));
+        assert_eq!(from_tokens, "allow(clippy :: all)");
+        let from_meta = to_string(NO_ANN, |s| s.print_attr_args(&normal.item, DUMMY_SP));
+        assert_eq!(from_meta, "allow(clippy::all)");
+        with_fn_item("frob", |item| {
+            assert_eq!(
+                item_to_string(item, &[allow], false).trim_end(),
+                "#[allow(clippy::all)]\nfn frob()"
+            );
+        });
+    })
+}
+
+#[test]
+fn test_inferred_const_arg() {
+    create_default_session_globals_then(|| {
+        // `Foo<_>`
+        let infer_args = [hir::GenericArg::Infer(hir::InferArg {
+            hir_id: hir::HirId::INVALID,
+            span: DUMMY_SP,
+        })];
+        let args = hir::GenericArgs { args: &infer_args, ..hir::GenericArgs::none() };
+        let foo_segments = [hir::PathSegment { args: Some(&args), ..segment("Foo") }];
+        let foo_path = path(&foo_segments);
+        let foo = ty(hir::TyKind::Path(hir::QPath::Resolved(None, &foo_path)));
+        assert_eq!(ty_to_string(&foo, false), "Foo<_>");
+    })
+}
+
+fn fn_header() -> hir::FnHeader {
+    hir::FnHeader {
+        unsafety: hir::Unsafety::Normal,
+        constness: hir::Constness::NotConst,
+        asyncness: hir::IsAsync::NotAsync,
+        abi: Abi::Rust,
+    }
+}
+
+fn fn_decl<'hir>(
+    inputs: &'hir [hir::Ty<'hir>],
+    implicit_self: hir::ImplicitSelfKind,
+) -> hir::FnDecl<'hir> {
+    hir::FnDecl {
+        inputs,
+        output: hir::FnRetTy::DefaultReturn(DUMMY_SP),
+        c_variadic: false,
+        implicit_self,
+        lifetime_elision_allowed: true,
+    }
+}
+
+#[test]
+fn test_mut_params() {
+    create_default_session_globals_then(|| {
+        let header = fn_header();
+
+        // `fn g(&mut self)`
+        let self_segments = [segment("Self")];
+        let self_path = path(&self_segments);
+        let self_ty = ty(hir::TyKind::Path(hir::QPath::Resolved(None, &self_path)));
+        let elided = lifetime("", hir::LifetimeName::Infer);
+        let self_mut_ref = hir::MutTy { ty: &self_ty, mutbl: hir::Mutability::Mut };
+        let inputs = [ty(hir::TyKind::Ref(&elided, self_mut_ref))];
+        let decl = fn_decl(&inputs, hir::ImplicitSelfKind::MutRef);
+        let self_names = [Ident::with_dummy_span(kw::SelfLower)];
+        let printed = to_string(NO_ANN, |s| {
+            let name = Some(Symbol::intern("g"));
+            s.print_fn(&decl, header, name, hir::Generics::empty(), &self_names, None)
+        });
+        assert_eq!(printed, "fn g(&mut self)");
+
+        // `fn h(mut self)`
+        let inputs = [self_ty];
+        let decl = fn_decl(&inputs, hir::ImplicitSelfKind::Mut);
+        let printed = to_string(NO_ANN, |s| {
+            let name = Some(Symbol::intern("h"));
+            s.print_fn(&decl, header, name, hir::Generics::empty(), &self_names, None)
+        });
+        assert_eq!(printed, "fn h(mut self)");
+    })
+}
+
+fn sugared_expr_to_string(expr: &hir::Expr<'_>) -> String {
+    to_string(NO_ANN, |s| {
+        s.sugared = true;
+        s.print_expr(expr)
+    })
+}
+
+#[test]
+fn test_sugared_ranges() {
+    create_default_session_globals_then(|| {
+        let (one, two) = (int_lit(1), int_lit(2));
+        let (one, two) = (expr(hir::ExprKind::Lit(&one)), expr(hir::ExprKind::Lit(&two)));
+        let range_field = |name, expr| hir::ExprField {
+            hir_id: hir::HirId::INVALID,
+            ident: Ident::with_dummy_span(name),
+            expr,
+            span: DUMMY_SP,
+            is_shorthand: false,
+        };
+
+        // `1..2`, as lowered to `Range { start: 1, end: 2 }`
+        let range = hir::QPath::LangItem(hir::LangItem::Range, DUMMY_SP, None);
+        let fields = [range_field(sym::start, &one), range_field(sym::end, &two)];
+        let exclusive = expr(hir::ExprKind::Struct(&range, &fields, None));
+        assert_eq!(sugared_expr_to_string(&exclusive), "1..2");
+        assert!(expr_to_string(&exclusive).starts_with("#[lang = \"Range\"]{"));
+
+        // `1..=2`, as lowered to `RangeInclusive::new(1, 2)`
+        let new = hir::QPath::LangItem(hir::LangItem::RangeInclusiveNew, DUMMY_SP, None);
+        let new = expr(hir::ExprKind::Path(new));
+        let args = [one, two];
+        let inclusive = expr(hir::ExprKind::Call(&new, &args));
+        assert_eq!(sugared_expr_to_string(&inclusive), "1..=2");
+
+        // `(1..=2).rev()`
+        let rev = segment("rev");
+        let rev = expr(hir::ExprKind::MethodCall(&rev, &inclusive, &[], DUMMY_SP));
+        assert_eq!(sugared_expr_to_string(&rev), "(1..=2).rev()");
+    })
+}
+
+#[test]
+fn test_async_fn_header() {
+    create_default_session_globals_then(|| {
+        let decl = fn_decl(&[], hir::ImplicitSelfKind::None);
+        let header_to_string = |constness, unsafety| {
+            let header = hir::FnHeader {
+                unsafety,
+                constness,
+                asyncness: hir::IsAsync::Async,
+                ..fn_header()
+            };
+            to_string(NO_ANN, |s| {
+                let name = Some(Symbol::intern("f"));
+                s.print_fn(&decl, header, name, hir::Generics::empty(), &[], None)
+            })
+        };
+        let (not_const, normal) = (hir::Constness::NotConst, hir::Unsafety::Normal);
+
+        assert_eq!(header_to_string(not_const, normal), "async fn f()");
+        assert_eq!(header_to_string(hir::Constness::Const, normal), "const async fn f()");
+        assert_eq!(header_to_string(not_const, hir::Unsafety::Unsafe), "async unsafe fn f()");
+    })
+}
+
+#[test]
+fn test_const_impl_method() {
+    create_default_session_globals_then(|| {
+        // `const fn f() {}` in an impl
+        let decl = fn_decl(&[], hir::ImplicitSelfKind::None);
+        let header = hir::FnHeader { constness: hir::Constness::Const, ..fn_header() };
+        let sig = hir::FnSig { header, decl: &decl, span: DUMMY_SP };
+        let method = hir::ImplItem {
+            ident: Ident::from_str("f"),
+            owner_id: hir::CRATE_OWNER_ID,
+            generics: hir::Generics::empty(),
+            kind: hir::ImplItemKind::Fn(sig, hir::BodyId { hir_id: hir::HirId::INVALID }),
+            defaultness: hir::Defaultness::Final,
+            span: DUMMY_SP,
+            vis_span: DUMMY_SP,
+        };
+        let printed = to_string(NO_ANN, |s| s.print_impl_item(&method));
+        assert!(printed.starts_with("const fn f()"), "{printed}");
+    })
+}
+
+#[test]
+fn test_impl_trait_in_arg_position() {
+    create_default_session_globals_then(|| {
+        // `fn f(x: impl Foo)`, lowered to `fn f<impl Foo>(x: impl Foo) where impl Foo: Foo`
+        let foo_segments = [segment("Foo")];
+        let foo_path = path(&foo_segments);
+        let impl_foo_segments = [segment("impl Foo")];
+        let impl_foo_path = path(&impl_foo_segments);
+        let impl_foo = ty(hir::TyKind::Path(hir::QPath::Resolved(None, &impl_foo_path)));
+        let params = [hir::GenericParam {
+            hir_id: hir::HirId::INVALID,
+            def_id: hir::CRATE_OWNER_ID.def_id,
+            name: hir::ParamName::Plain(Ident::from_str("impl Foo")),
+            pure_wrt_drop: false,
+            span: DUMMY_SP,
+            kind: GenericParamKind::Type { default: None, synthetic: true },
+            colon_span: None,
+            source: hir::GenericParamSource::Generics,
+        }];
+        let bounds =
+            [hir::GenericBound::Trait(poly_trait_ref(&foo_path), hir::TraitBoundModifier::None)];
+        let predicates = [hir::WherePredicate::BoundPredicate(hir::WhereBoundPredicate {
+            hir_id: hir::HirId::INVALID,
+            span: DUMMY_SP,
+            origin: hir::PredicateOrigin::ImplTrait,
+            bound_generic_params: &[],
+            bounded_ty: &impl_foo,
+            bounds: &bounds,
+        })];
+        let generics = hir::Generics {
+            params: &params,
+            predicates: &predicates,
+            ..*hir::Generics::empty()
+        };
+        let inputs = [impl_foo];
+        let decl = fn_decl(&inputs, hir::ImplicitSelfKind::None);
+        let header = fn_header();
+        let x = [Ident::from_str("x")];
+        let printed = to_string(NO_ANN, |s| {
+            s.print_fn(&decl, header, Some(Symbol::intern("f")), &generics, &x, None)
+        });
+        assert_eq!(printed, "fn f(x: impl Foo)");
+    })
+}
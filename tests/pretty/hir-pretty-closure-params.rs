@@ -0,0 +1,7 @@
+// pretty-compare-only
+// pretty-mode:hir
+// pp-exact:hir-pretty-closure-params.pp
+
+pub fn foo() {
+    let _f = |x: u8| x;
+}